@@ -0,0 +1,22 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
+
+use svg_report::input;
+use svg_report::report::{self, ReportOptions};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let eval = input::load(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/examples/jane_doe.json"
+    ))?;
+    let doc = report::render(&eval, &ReportOptions::default())?;
+
+    // Save the PDF
+    let file = File::create("audiogram_layout.pdf")?;
+    let mut writer = BufWriter::new(file);
+    doc.save(&mut writer)?;
+
+    println!("Successfully created audiogram_layout.pdf");
+    Ok(())
+}
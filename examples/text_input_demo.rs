@@ -0,0 +1,123 @@
+use printpdf::*;
+use std::{fs::File, io::BufWriter};
+
+use svg_report::canvas::PdfCanvas;
+use svg_report::shapes::{draw_symbol, Anchor, BoundingBox, Symbol, SymbolColor, SymbolStyle};
+use svg_report::text::{draw_text_in_box, HAlign, TextMeasurer, VAlign};
+use svg_report::widgets::{draw_curved_sides_rect, draw_rounded_rect};
+
+fn main() {
+    /* ── create a Letter page ─────────────────────────────────────────── */
+    let (doc, page, layer) = PdfDocument::new("Text‑input demo", Mm(215.9), Mm(279.4), "Layer 1");
+    let layer = doc.get_page(page).get_layer(layer);
+
+    // load an external font so we can query exact glyph metrics
+    let font_bytes = include_bytes!("../vendor/printpdf/assets/fonts/RobotoMedium.ttf");
+    let mut reader = std::io::Cursor::new(font_bytes.as_ref());
+    let font = doc.add_external_font(&mut reader).unwrap();
+    let mut canvas = PdfCanvas::new(layer.clone(), font.clone());
+
+    /* ── geometry parameters ─────────────────────────────────────────── */
+    let rect_w = Mm(80.0); // width  80 mm
+    let rect_h = Mm(15.0); // height 15 mm
+    let origin = (Mm(65.0), Mm(140.0)); // lower‑left corner on page
+
+    /* ── draw text input outline with curved sides ───────────────────── */
+    draw_curved_sides_rect(&mut canvas, origin, rect_w, rect_h);
+
+    // draw a rounded rectangle below the pill
+    let rounded_rect_origin = (Mm(origin.0 .0), Mm(origin.1 .0 - 25.0));
+    let rounded_rect_w = rect_w;
+    let rounded_rect_h = Mm(20.0);
+    let corner_radius = Mm(4.0);
+    draw_rounded_rect(
+        &mut canvas,
+        rounded_rect_origin,
+        rounded_rect_w,
+        rounded_rect_h,
+        corner_radius,
+    );
+
+    /* ── add centred text ────────────────────────────────────────────── */
+
+    let caption = "Jane Doe";
+    let font_size_pt: f32 = 10.0;
+
+    // measure with the same face, kerning included
+    let measurer = TextMeasurer::new(font_bytes.to_vec()).unwrap();
+
+    // centre inside the rounded rectangle
+    let (x0, y0) = rounded_rect_origin;
+    let rect = BoundingBox {
+        left: x0.0,
+        bottom: y0.0,
+        right: x0.0 + rounded_rect_w.0,
+        top: y0.0 + rounded_rect_h.0,
+    };
+    draw_text_in_box(
+        &mut canvas,
+        caption,
+        &rect,
+        HAlign::Center,
+        VAlign::Middle,
+        &measurer,
+        font_size_pt,
+    )
+    .unwrap();
+
+    // draw various audiogram symbols below
+    let mut sx = Mm(origin.0 .0);
+    let sy = Mm(origin.1 .0 - 50.0);
+    let step = Mm(6.0);
+    let symbols = [
+        Symbol::Square,
+        Symbol::SquareFilled,
+        Symbol::Triangle,
+        Symbol::TriangleFilled,
+        Symbol::Circle,
+        Symbol::CircleFilled,
+        Symbol::S,
+        Symbol::SFilled,
+        Symbol::U,
+        Symbol::UFilled,
+        Symbol::X,
+        Symbol::XFilled,
+        Symbol::A,
+        Symbol::AFilled,
+        Symbol::Greater,
+        Symbol::GreaterFilled,
+        Symbol::Less,
+        Symbol::LessFilled,
+        Symbol::LeftBracket,
+        Symbol::LeftBracketFilled,
+        Symbol::RightBracket,
+        Symbol::RightBracketFilled,
+        Symbol::Star,
+        Symbol::StarFilled,
+        Symbol::ArrowDownRight,
+        Symbol::ArrowDownRightFilled,
+        Symbol::ArrowDownLeft,
+        Symbol::ArrowDownLeftFilled,
+        Symbol::VT,
+        Symbol::VTFilled,
+    ];
+    for (i, sym) in symbols.iter().enumerate() {
+        let color = if i % 2 == 0 {
+            SymbolColor::RED
+        } else {
+            SymbolColor::BLUE
+        };
+        let style = SymbolStyle {
+            anchor: Anchor::BottomLeft,
+            ..SymbolStyle::new(10.0, color)
+        };
+        draw_symbol(&mut canvas, *sym, (sx, sy), &style);
+        sx = Mm(sx.0 + step.0);
+    }
+
+    /* ── save ─────────────────────────────────────────────────────────── */
+    doc.save(&mut BufWriter::new(
+        File::create("text_input_demo.pdf").unwrap(),
+    ))
+    .unwrap();
+}
@@ -0,0 +1,968 @@
+//! Drawing surface the report is laid out on.
+//!
+//! Layout code draws through the [`Canvas`] trait only, so the same page
+//! can be written as PDF ([`PdfCanvas`]), as standalone SVG
+//! ([`SvgCanvas`]) or recorded for tests ([`RecordingCanvas`]).
+//! Coordinates are millimetres from the lower-left corner of the page, as
+//! in PDF; line widths and font sizes are in points.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::Object;
+use printpdf::{CurTransMat, IndirectFontRef, Mm, PdfLayerReference, Pt};
+
+use crate::shapes::{pt_to_mm, SymbolColor};
+use crate::text::Glyph;
+
+/// Thinnest line drawn, in points. PDF would show a zero-width line as one
+/// device pixel and SVG would hide it, so both draw this width instead.
+pub const HAIRLINE_PT: f32 = 0.25;
+
+/// How a path is painted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Paint {
+    Stroke,
+    Fill,
+    FillStroke,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathOp {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    /// Cubic bezier: two control points, then the end point.
+    CurveTo((f32, f32), (f32, f32), (f32, f32)),
+    Close,
+}
+
+/// Outline made of straight and cubic bezier segments, in millimetres.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    pub ops: Vec<PathOp>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Path::default()
+    }
+
+    /// Open polyline through `points`.
+    pub fn polyline(points: &[(f32, f32)]) -> Self {
+        let mut path = Path::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            path = if i == 0 {
+                path.move_to(x, y)
+            } else {
+                path.line_to(x, y)
+            };
+        }
+        path
+    }
+
+    /// Closed polygon through `points`.
+    pub fn polygon(points: &[(f32, f32)]) -> Self {
+        Path::polyline(points).close()
+    }
+
+    /// Smooth open curve through `points`: a Catmull-Rom spline, as cubic
+    /// bezier segments.
+    pub fn smooth_curve(points: &[(f32, f32)]) -> Self {
+        let Some(&(x, y)) = points.first() else {
+            return Path::new();
+        };
+        let mut path = Path::new().move_to(x, y);
+        for i in 1..points.len() {
+            let p0 = points[i.saturating_sub(2)];
+            let (p1, p2) = (points[i - 1], points[i]);
+            let p3 = points[(i + 1).min(points.len() - 1)];
+            path = path.curve_to(
+                (p1.0 + (p2.0 - p0.0) / 6.0, p1.1 + (p2.1 - p0.1) / 6.0),
+                (p2.0 - (p3.0 - p1.0) / 6.0, p2.1 - (p3.1 - p1.1) / 6.0),
+                p2,
+            );
+        }
+        path
+    }
+
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.ops.push(PathOp::MoveTo(x, y));
+        self
+    }
+
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.ops.push(PathOp::LineTo(x, y));
+        self
+    }
+
+    pub fn curve_to(mut self, c1: (f32, f32), c2: (f32, f32), end: (f32, f32)) -> Self {
+        self.ops.push(PathOp::CurveTo(c1, c2, end));
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.ops.push(PathOp::Close);
+        self
+    }
+}
+
+/// A page being drawn. Colors, line width and dash pattern are graphics
+/// state: they apply to everything drawn after them, until changed or
+/// until the matching [`Canvas::restore_graphics_state`].
+pub trait Canvas {
+    fn save_graphics_state(&mut self);
+    fn restore_graphics_state(&mut self);
+
+    /// Map later drawing through `(x, y) -> (dx + scale * x, dy + scale * y)`,
+    /// on top of the current transform. Offsets are in millimetres.
+    fn transform(&mut self, dx: f32, dy: f32, scale: f32);
+
+    /// Color of filled paths and text.
+    fn set_fill_color(&mut self, color: SymbolColor);
+    fn set_outline_color(&mut self, color: SymbolColor);
+    /// Line width in points. Widths under [`HAIRLINE_PT`], zero included,
+    /// draw hairlines of that width.
+    fn set_outline_thickness(&mut self, thickness: f32);
+    /// Dash and gap lengths in points; `None` draws solid lines.
+    fn set_line_dash(&mut self, dash: Option<(f32, f32)>);
+
+    fn draw_path(&mut self, path: &Path, paint: Paint);
+
+    /// Draw `text` in the primary font with its baseline starting at
+    /// `(x, y)`.
+    fn use_text(&mut self, text: &str, font_size: f32, x: Mm, y: Mm);
+
+    /// Draw `text` mapped to `glyphs` by a [`TextMeasurer`], applying their
+    /// kerning so the text takes the measured width. The glyphs all come
+    /// from the same face, and the text is set in the canvas font with that
+    /// face's id. Backends whose text engine kerns on its own draw it as
+    /// plain text.
+    ///
+    /// [`TextMeasurer`]: crate::text::TextMeasurer
+    fn use_kerned_text(
+        &mut self,
+        text: &str,
+        glyphs: &[Glyph],
+        units_per_em: u16,
+        font_size: f32,
+        x: Mm,
+        y: Mm,
+    );
+
+    /// Straight line from `from` to `to`.
+    fn line(&mut self, from: (f32, f32), to: (f32, f32)) {
+        self.draw_path(&Path::polyline(&[from, to]), Paint::Stroke);
+    }
+
+    fn rect(&mut self, left: f32, bottom: f32, right: f32, top: f32, paint: Paint) {
+        let path = Path::polygon(&[(left, bottom), (right, bottom), (right, top), (left, top)]);
+        self.draw_path(&path, paint);
+    }
+}
+
+// --- Recording ---
+
+/// One call made on a [`RecordingCanvas`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    SaveGraphicsState,
+    RestoreGraphicsState,
+    Transform(f32, f32, f32),
+    SetFillColor(SymbolColor),
+    SetOutlineColor(SymbolColor),
+    SetOutlineThickness(f32),
+    SetLineDash(Option<(f32, f32)>),
+    DrawPath(Path, Paint),
+    Text {
+        text: String,
+        /// Id of the face the text is set in.
+        font: usize,
+        font_size: f32,
+        x: f32,
+        y: f32,
+    },
+}
+
+/// Canvas that only records what is drawn on it, for tests and for
+/// measuring a layout before it is drawn for real.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingCanvas {
+    pub commands: Vec<Command>,
+}
+
+impl RecordingCanvas {
+    /// The paths drawn, in order.
+    pub fn paths(&self) -> impl Iterator<Item = (&Path, Paint)> {
+        self.commands.iter().filter_map(|c| match c {
+            Command::DrawPath(path, paint) => Some((path, *paint)),
+            _ => None,
+        })
+    }
+
+    /// Every character drawn, by the id of the face it is set in.
+    pub fn characters(&self) -> BTreeMap<usize, BTreeSet<char>> {
+        let mut characters = BTreeMap::<usize, BTreeSet<char>>::new();
+        for c in &self.commands {
+            if let Command::Text { text, font, .. } = c {
+                characters.entry(*font).or_default().extend(text.chars());
+            }
+        }
+        characters
+    }
+}
+
+impl Canvas for RecordingCanvas {
+    fn save_graphics_state(&mut self) {
+        self.commands.push(Command::SaveGraphicsState);
+    }
+
+    fn restore_graphics_state(&mut self) {
+        self.commands.push(Command::RestoreGraphicsState);
+    }
+
+    fn transform(&mut self, dx: f32, dy: f32, scale: f32) {
+        self.commands.push(Command::Transform(dx, dy, scale));
+    }
+
+    fn set_fill_color(&mut self, color: SymbolColor) {
+        self.commands.push(Command::SetFillColor(color));
+    }
+
+    fn set_outline_color(&mut self, color: SymbolColor) {
+        self.commands.push(Command::SetOutlineColor(color));
+    }
+
+    fn set_outline_thickness(&mut self, thickness: f32) {
+        self.commands.push(Command::SetOutlineThickness(thickness));
+    }
+
+    fn set_line_dash(&mut self, dash: Option<(f32, f32)>) {
+        self.commands.push(Command::SetLineDash(dash));
+    }
+
+    fn draw_path(&mut self, path: &Path, paint: Paint) {
+        self.commands.push(Command::DrawPath(path.clone(), paint));
+    }
+
+    fn use_text(&mut self, text: &str, font_size: f32, x: Mm, y: Mm) {
+        self.use_kerned_text(text, &[], 0, font_size, x, y);
+    }
+
+    fn use_kerned_text(
+        &mut self,
+        text: &str,
+        glyphs: &[Glyph],
+        _units_per_em: u16,
+        font_size: f32,
+        x: Mm,
+        y: Mm,
+    ) {
+        self.commands.push(Command::Text {
+            text: text.to_string(),
+            font: glyphs.first().map_or(0, |g| g.font),
+            font_size,
+            x: x.0,
+            y: y.0,
+        });
+    }
+}
+
+// --- PDF ---
+
+/// Draws onto a printpdf layer.
+pub struct PdfCanvas {
+    layer: PdfLayerReference,
+    /// Indexed by face id.
+    fonts: Vec<IndirectFontRef>,
+}
+
+impl PdfCanvas {
+    /// Canvas writing all text in one font.
+    pub fn new(layer: PdfLayerReference, font: IndirectFontRef) -> Self {
+        PdfCanvas::with_fonts(layer, vec![font])
+    }
+
+    /// Canvas writing text in `fonts`, in face id order: usually one per
+    /// face of a [`FontRegistry`].
+    ///
+    /// [`FontRegistry`]: crate::fonts::FontRegistry
+    pub fn with_fonts(layer: PdfLayerReference, fonts: Vec<IndirectFontRef>) -> Self {
+        PdfCanvas { layer, fonts }
+    }
+}
+
+impl Canvas for PdfCanvas {
+    fn save_graphics_state(&mut self) {
+        self.layer.save_graphics_state();
+    }
+
+    fn restore_graphics_state(&mut self) {
+        self.layer.restore_graphics_state();
+    }
+
+    fn transform(&mut self, dx: f32, dy: f32, scale: f32) {
+        self.layer
+            .set_ctm(CurTransMat::Translate(Mm(dx).into(), Mm(dy).into()));
+        self.layer.set_ctm(CurTransMat::Scale(scale, scale));
+    }
+
+    fn set_fill_color(&mut self, color: SymbolColor) {
+        self.layer.set_fill_color(color.to_color());
+    }
+
+    fn set_outline_color(&mut self, color: SymbolColor) {
+        self.layer.set_outline_color(color.to_color());
+    }
+
+    fn set_outline_thickness(&mut self, thickness: f32) {
+        self.layer.set_outline_thickness(thickness.max(HAIRLINE_PT));
+    }
+
+    fn set_line_dash(&mut self, dash: Option<(f32, f32)>) {
+        // printpdf's LineDashPattern takes whole points only
+        let array = match dash {
+            Some((dash, gap)) => vec![dash.into(), gap.into()],
+            None => Vec::new(),
+        };
+        self.layer
+            .add_operation(Operation::new("d", vec![Object::Array(array), 0.into()]));
+    }
+
+    fn draw_path(&mut self, path: &Path, paint: Paint) {
+        let pt = |(x, y): (f32, f32)| -> [Object; 2] {
+            [Pt::from(Mm(x)).0.into(), Pt::from(Mm(y)).0.into()]
+        };
+        let mut ops = Vec::with_capacity(path.ops.len() + 1);
+        for op in &path.ops {
+            ops.push(match *op {
+                PathOp::MoveTo(x, y) => Operation::new("m", pt((x, y)).to_vec()),
+                PathOp::LineTo(x, y) => Operation::new("l", pt((x, y)).to_vec()),
+                PathOp::CurveTo(c1, c2, end) => {
+                    Operation::new("c", [pt(c1), pt(c2), pt(end)].concat())
+                }
+                PathOp::Close => Operation::new("h", vec![]),
+            });
+        }
+        ops.push(Operation::new(
+            match paint {
+                Paint::Stroke => "S",
+                Paint::Fill => "f",
+                Paint::FillStroke => "B",
+            },
+            vec![],
+        ));
+        for op in ops {
+            self.layer.add_operation(op);
+        }
+    }
+
+    fn use_text(&mut self, text: &str, font_size: f32, x: Mm, y: Mm) {
+        self.layer.use_text(text, font_size, x, y, &self.fonts[0]);
+    }
+
+    fn use_kerned_text(
+        &mut self,
+        text: &str,
+        glyphs: &[Glyph],
+        units_per_em: u16,
+        font_size: f32,
+        x: Mm,
+        y: Mm,
+    ) {
+        let font = &self.fonts[glyphs.first().map_or(0, |g| g.font)];
+        // Builtin fonts never kern and must go through their own encoding
+        if glyphs.iter().all(|g| g.kerning == 0) {
+            return self.layer.use_text(text, font_size, x, y, font);
+        }
+        // TJ adjustments are in thousandths of an em and move the next glyph
+        // left, before it is shown
+        let mut adjust = 0;
+        let codepoints: Vec<(i64, u16)> = glyphs
+            .iter()
+            .map(|g| {
+                let item = (adjust, g.id.0);
+                adjust = (-(g.kerning as f32) * 1000.0 / units_per_em as f32).round() as i64;
+                item
+            })
+            .collect();
+        self.layer.begin_text_section();
+        self.layer.set_font(font, font_size);
+        self.layer.set_text_cursor(x, y);
+        self.layer.write_positioned_codepoints(codepoints);
+        self.layer.end_text_section();
+    }
+}
+
+// --- SVG ---
+
+/// Font the SVG text is set in.
+#[derive(Clone, Debug)]
+pub enum SvgFont {
+    /// A CSS font-family list, resolved by the viewer, at a CSS weight and
+    /// upright or italic.
+    Family {
+        family: String,
+        weight: u16,
+        italic: bool,
+    },
+    /// TrueType data embedded in the document, so viewers set text in the
+    /// same font as the PDF.
+    Embedded(Vec<u8>),
+}
+
+#[derive(Copy, Clone)]
+struct SvgState {
+    fill: SymbolColor,
+    outline: SymbolColor,
+    thickness: f32,
+    dash: Option<(f32, f32)>,
+    // page = offset + scale * user
+    dx: f32,
+    dy: f32,
+    scale: f32,
+}
+
+impl SvgState {
+    /// The PDF initial graphics state.
+    const INITIAL: SvgState = SvgState {
+        fill: SymbolColor::BLACK,
+        outline: SymbolColor::BLACK,
+        thickness: 1.0,
+        dash: None,
+        dx: 0.0,
+        dy: 0.0,
+        scale: 1.0,
+    };
+}
+
+/// Builds a standalone SVG document, one millimetre per user unit. Pages
+/// are stacked top to bottom.
+pub struct SvgCanvas {
+    width: f32,
+    height: f32,
+    pages: u32,
+    /// Indexed by face id.
+    fonts: Vec<SvgFont>,
+    state: SvgState,
+    stack: Vec<SvgState>,
+    body: String,
+}
+
+/// CSS family of the embedded font of face `font`.
+fn embedded_font_family(font: usize) -> String {
+    match font {
+        0 => "report-font".to_string(),
+        _ => format!("report-font-{}", font),
+    }
+}
+
+impl SvgCanvas {
+    /// Empty page of `width` by `height` millimetres, with all text in one
+    /// font.
+    pub fn new(width: f32, height: f32, font: SvgFont) -> Self {
+        SvgCanvas::with_fonts(width, height, vec![font])
+    }
+
+    /// Empty page with text in `fonts`, in face id order, as
+    /// [`PdfCanvas::with_fonts`].
+    pub fn with_fonts(width: f32, height: f32, fonts: Vec<SvgFont>) -> Self {
+        SvgCanvas {
+            width,
+            height,
+            pages: 1,
+            fonts,
+            state: SvgState::INITIAL,
+            stack: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    /// Start a new page below the current one, in the initial graphics
+    /// state.
+    pub fn add_page(&mut self) {
+        self.pages += 1;
+        self.state = SvgState::INITIAL;
+        self.stack.clear();
+    }
+
+    /// The finished SVG document.
+    pub fn finish(self) -> String {
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}mm" height="{h}mm" viewBox="0 0 {w} {h}">"#,
+            w = self.width,
+            h = self.height * self.pages as f32,
+        );
+        for (i, font) in self.fonts.iter().enumerate() {
+            if let SvgFont::Embedded(bytes) = font {
+                let _ = writeln!(
+                    svg,
+                    "<style>@font-face {{ font-family: \"{}\"; src: url(data:font/ttf;base64,{}); }}</style>",
+                    embedded_font_family(i),
+                    base64(bytes)
+                );
+            }
+        }
+        svg.push_str(&self.body);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Document coordinates, flipped so y runs down as SVG expects, on the
+    /// current page.
+    fn point(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let s = &self.state;
+        let page_bottom = self.height * self.pages as f32;
+        (s.dx + s.scale * x, page_bottom - (s.dy + s.scale * y))
+    }
+
+    /// Points to page millimetres, under the current scale.
+    fn length(&self, pt: f32) -> f32 {
+        pt_to_mm(pt) * self.state.scale
+    }
+
+    /// `font-family` and the other font attributes of face `font`.
+    fn font_attributes(&self, font: usize) -> String {
+        match &self.fonts[font] {
+            SvgFont::Family {
+                family,
+                weight,
+                italic,
+            } => {
+                let mut attributes = format!(r#"font-family="{}""#, escape(family));
+                if *weight != 400 {
+                    let _ = write!(attributes, r#" font-weight="{}""#, weight);
+                }
+                if *italic {
+                    attributes.push_str(r#" font-style="italic""#);
+                }
+                attributes
+            }
+            SvgFont::Embedded(_) => format!(r#"font-family="{}""#, embedded_font_family(font)),
+        }
+    }
+}
+
+impl Canvas for SvgCanvas {
+    fn save_graphics_state(&mut self) {
+        self.stack.push(self.state);
+    }
+
+    fn restore_graphics_state(&mut self) {
+        if let Some(state) = self.stack.pop() {
+            self.state = state;
+        }
+    }
+
+    fn transform(&mut self, dx: f32, dy: f32, scale: f32) {
+        let s = &mut self.state;
+        s.dx += s.scale * dx;
+        s.dy += s.scale * dy;
+        s.scale *= scale;
+    }
+
+    fn set_fill_color(&mut self, color: SymbolColor) {
+        self.state.fill = color;
+    }
+
+    fn set_outline_color(&mut self, color: SymbolColor) {
+        self.state.outline = color;
+    }
+
+    fn set_outline_thickness(&mut self, thickness: f32) {
+        self.state.thickness = thickness;
+    }
+
+    fn set_line_dash(&mut self, dash: Option<(f32, f32)>) {
+        self.state.dash = dash;
+    }
+
+    fn draw_path(&mut self, path: &Path, paint: Paint) {
+        let mut d = String::new();
+        for op in &path.ops {
+            match *op {
+                PathOp::MoveTo(x, y) => {
+                    let (x, y) = self.point((x, y));
+                    let _ = write!(d, "M{} {} ", num(x), num(y));
+                }
+                PathOp::LineTo(x, y) => {
+                    let (x, y) = self.point((x, y));
+                    let _ = write!(d, "L{} {} ", num(x), num(y));
+                }
+                PathOp::CurveTo(c1, c2, end) => {
+                    d.push('C');
+                    for p in [c1, c2, end] {
+                        let (x, y) = self.point(p);
+                        let _ = write!(d, "{} {} ", num(x), num(y));
+                    }
+                }
+                PathOp::Close => d.push_str("Z "),
+            }
+        }
+
+        let fill = match paint {
+            Paint::Stroke => "none".to_string(),
+            Paint::Fill | Paint::FillStroke => css_color(self.state.fill),
+        };
+        let _ = write!(self.body, r#"<path d="{}" fill="{}""#, d.trim_end(), fill);
+        if paint != Paint::Fill {
+            let width = self.length(self.state.thickness.max(HAIRLINE_PT));
+            let _ = write!(
+                self.body,
+                r#" stroke="{}" stroke-width="{}""#,
+                css_color(self.state.outline),
+                num(width)
+            );
+            if let Some((dash, gap)) = self.state.dash {
+                let _ = write!(
+                    self.body,
+                    r#" stroke-dasharray="{} {}""#,
+                    num(self.length(dash)),
+                    num(self.length(gap))
+                );
+            }
+        }
+        self.body.push_str("/>\n");
+    }
+
+    fn use_text(&mut self, text: &str, font_size: f32, x: Mm, y: Mm) {
+        self.use_kerned_text(text, &[], 0, font_size, x, y);
+    }
+
+    fn use_kerned_text(
+        &mut self,
+        text: &str,
+        glyphs: &[Glyph],
+        _units_per_em: u16,
+        font_size: f32,
+        x: Mm,
+        y: Mm,
+    ) {
+        let font = glyphs.first().map_or(0, |g| g.font);
+        let (x, y) = self.point((x.0, y.0));
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{}" y="{}" {} font-size="{}" fill="{}" xml:space="preserve">{}</text>"#,
+            num(x),
+            num(y),
+            self.font_attributes(font),
+            num(self.length(font_size)),
+            css_color(self.state.fill),
+            escape(text)
+        );
+    }
+}
+
+/// Coordinates to 1/1000 mm, without trailing zeros.
+fn num(v: f32) -> String {
+    let s = format!("{:.3}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn css_color(color: SymbolColor) -> String {
+    let (r, g, b) = match color {
+        SymbolColor::Rgb(r, g, b) => (r, g, b),
+        // naive conversion, as PDF viewers do without a color profile
+        SymbolColor::Cmyk(c, m, y, k) => (
+            (1.0 - c) * (1.0 - k),
+            (1.0 - m) * (1.0 - k),
+            (1.0 - y) * (1.0 - k),
+        ),
+        SymbolColor::Gray(g) => (g, g, g),
+    };
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b))
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use printpdf::lopdf::content::Content;
+    use printpdf::{lopdf, BuiltinFont, PdfDocument};
+
+    fn svg_canvas() -> SvgCanvas {
+        SvgCanvas::new(
+            100.0,
+            50.0,
+            SvgFont::Family {
+                family: "Helvetica".to_string(),
+                weight: 400,
+                italic: false,
+            },
+        )
+    }
+
+    /// The `<path>` elements of an SVG document.
+    fn svg_paths(svg: &str) -> Vec<&str> {
+        svg.lines()
+            .filter(|line| line.starts_with("<path"))
+            .collect()
+    }
+
+    /// Content stream operations of each page of a 100 x 50 mm PDF, drawn
+    /// page by page by `draw`.
+    fn pdf_pages(pages: usize, draw: impl Fn(&mut PdfCanvas, usize)) -> Vec<Vec<Operation>> {
+        let (doc, page, layer) = PdfDocument::new("test", Mm(100.0), Mm(50.0), "Layer 1");
+        let font = doc.add_builtin_font(BuiltinFont::Helvetica).unwrap();
+        let mut layers = vec![doc.get_page(page).get_layer(layer)];
+        for _ in 1..pages {
+            let (page, layer) = doc.add_page(Mm(100.0), Mm(50.0), "Layer 1");
+            layers.push(doc.get_page(page).get_layer(layer));
+        }
+        for (i, layer) in layers.into_iter().enumerate() {
+            draw(&mut PdfCanvas::new(layer, font.clone()), i);
+        }
+
+        let pdf = lopdf::Document::load_mem(&doc.save_to_bytes().unwrap()).unwrap();
+        pdf.get_pages()
+            .values()
+            .map(|&id| {
+                Content::decode(&pdf.get_page_content(id).unwrap())
+                    .unwrap()
+                    .operations
+            })
+            .collect()
+    }
+
+    /// Numeric operands of every `operator` operation.
+    fn operands(ops: &[Operation], operator: &str) -> Vec<Vec<f32>> {
+        ops.iter()
+            .filter(|op| op.operator == operator)
+            .map(|op| op.operands.iter().map(|o| o.as_float().unwrap()).collect())
+            .collect()
+    }
+
+    fn mm(v: f32) -> f32 {
+        Pt::from(Mm(v)).0
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(
+            actual.len(),
+            expected.len(),
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn svg_flips_y_down() {
+        let mut canvas = svg_canvas();
+        canvas.line((10.0, 10.0), (20.0, 40.0));
+        canvas.use_text("a < b", 10.0, Mm(5.0), Mm(45.0));
+        let svg = canvas.finish();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="100mm" height="50mm" viewBox="0 0 100 50">"#));
+        assert!(svg_paths(&svg)[0].starts_with(r#"<path d="M10 40 L20 10" fill="none""#));
+        assert!(svg.contains(r#"<text x="5" y="5" font-family="Helvetica""#));
+        assert!(svg.contains(">a &lt; b</text>"));
+    }
+
+    #[test]
+    fn svg_transforms_nest_and_restore() {
+        let mut canvas = svg_canvas();
+        canvas.set_outline_thickness(1.0);
+        canvas.save_graphics_state();
+        canvas.transform(10.0, 5.0, 2.0);
+        canvas.transform(1.0, 1.0, 0.5);
+        canvas.line((0.0, 0.0), (10.0, 0.0));
+        canvas.restore_graphics_state();
+        canvas.line((0.0, 0.0), (10.0, 0.0));
+        let svg = canvas.finish();
+        let paths = svg_paths(&svg);
+        // scaled by 2 then 0.5, after moving 10 + 2 across and 5 + 2 up
+        let width = num(pt_to_mm(1.0));
+        assert_eq!(
+            paths[0],
+            format!(
+                r##"<path d="M12 43 L22 43" fill="none" stroke="#000000" stroke-width="{}"/>"##,
+                width
+            )
+        );
+        assert!(paths[1].starts_with(r#"<path d="M0 50 L10 50""#));
+    }
+
+    #[test]
+    fn svg_dashes_and_hairlines() {
+        let mut canvas = svg_canvas();
+        canvas.set_outline_thickness(0.0);
+        canvas.set_line_dash(Some((2.5, 1.5)));
+        canvas.line((0.0, 0.0), (10.0, 0.0));
+        canvas.set_line_dash(None);
+        canvas.rect(0.0, 0.0, 10.0, 10.0, Paint::Fill);
+        let svg = canvas.finish();
+        let paths = svg_paths(&svg);
+        assert!(paths[0].contains(&format!(
+            r#"stroke-width="{}" stroke-dasharray="{} {}""#,
+            num(pt_to_mm(HAIRLINE_PT)),
+            num(pt_to_mm(2.5)),
+            num(pt_to_mm(1.5))
+        )));
+        assert!(!paths[1].contains("stroke"));
+    }
+
+    #[test]
+    fn svg_pages_stack_downwards() {
+        let mut canvas = svg_canvas();
+        canvas.save_graphics_state();
+        canvas.transform(5.0, 5.0, 1.0);
+        canvas.add_page();
+        // the new page starts from the initial state, 50 mm lower
+        canvas.line((0.0, 0.0), (10.0, 50.0));
+        canvas.restore_graphics_state();
+        canvas.line((0.0, 0.0), (10.0, 0.0));
+        let svg = canvas.finish();
+        assert!(svg.contains(r#"viewBox="0 0 100 100""#));
+        let paths = svg_paths(&svg);
+        assert!(paths[0].starts_with(r#"<path d="M0 100 L10 50""#));
+        assert!(paths[1].starts_with(r#"<path d="M0 100 L10 100""#));
+    }
+
+    #[test]
+    fn svg_embeds_fonts_as_base64() {
+        let mut canvas = SvgCanvas::with_fonts(
+            100.0,
+            50.0,
+            vec![
+                SvgFont::Embedded(b"font".to_vec()),
+                SvgFont::Embedded(b"bold".to_vec()),
+            ],
+        );
+        canvas.use_text("x", 10.0, Mm(0.0), Mm(0.0));
+        let svg = canvas.finish();
+        assert!(
+            svg.contains(r#"font-family: "report-font"; src: url(data:font/ttf;base64,Zm9udA==);"#)
+        );
+        assert!(svg
+            .contains(r#"font-family: "report-font-1"; src: url(data:font/ttf;base64,Ym9sZA==);"#));
+        assert!(svg.contains(r#"font-family="report-font""#));
+
+        for (bytes, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"\xff\xfe\xfd", "//79"),
+        ] {
+            assert_eq!(base64(bytes), encoded);
+        }
+    }
+
+    #[test]
+    fn pdf_paths_are_in_points_from_the_bottom() {
+        let pages = pdf_pages(1, |canvas, _| {
+            canvas.draw_path(
+                &Path::polygon(&[(10.0, 10.0), (20.0, 40.0)]),
+                Paint::FillStroke,
+            )
+        });
+        let ops = &pages[0];
+        assert_close(&operands(ops, "m")[0], &[mm(10.0), mm(10.0)]);
+        assert_close(&operands(ops, "l")[0], &[mm(20.0), mm(40.0)]);
+        let painted: Vec<_> = ops
+            .iter()
+            .map(|op| op.operator.as_str())
+            .skip_while(|op| *op != "m")
+            .take(4)
+            .collect();
+        assert_eq!(painted, ["m", "l", "h", "B"]);
+    }
+
+    #[test]
+    fn pdf_transform_translates_then_scales() {
+        let pages = pdf_pages(1, |canvas, _| {
+            canvas.save_graphics_state();
+            canvas.transform(10.0, 5.0, 2.0);
+            canvas.restore_graphics_state();
+        });
+        let ops = &pages[0];
+        let cm = operands(ops, "cm");
+        assert_close(&cm[0], &[1.0, 0.0, 0.0, 1.0, mm(10.0), mm(5.0)]);
+        assert_close(&cm[1], &[2.0, 0.0, 0.0, 2.0, 0.0, 0.0]);
+        let operators: Vec<_> = ops.iter().map(|op| op.operator.as_str()).collect();
+        let cm = operators.iter().position(|op| *op == "cm").unwrap();
+        assert_eq!(operators[cm - 1..cm + 3], ["q", "cm", "cm", "Q"]);
+    }
+
+    #[test]
+    fn pdf_dashes_keep_fractions_and_hairlines_match_svg() {
+        let pages = pdf_pages(1, |canvas, _| {
+            canvas.set_line_dash(Some((2.5, 1.5)));
+            canvas.set_line_dash(None);
+            canvas.set_outline_thickness(0.0);
+            canvas.set_outline_thickness(0.75);
+        });
+        let ops = &pages[0];
+        let dashes: Vec<_> = ops
+            .iter()
+            .filter(|op| op.operator == "d")
+            .map(|op| {
+                let array = op.operands[0].as_array().unwrap();
+                array
+                    .iter()
+                    .map(|o| o.as_float().unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(dashes, [vec![2.5, 1.5], vec![]]);
+        assert_eq!(operands(ops, "w"), [vec![HAIRLINE_PT], vec![0.75]]);
+    }
+
+    #[test]
+    fn pdf_canvas_draws_on_its_own_page() {
+        let pages = pdf_pages(2, |canvas, page| {
+            let y = 10.0 * (page + 1) as f32;
+            canvas.line((0.0, y), (10.0, y));
+        });
+        assert_eq!(pages.len(), 2);
+        for (i, ops) in pages.iter().enumerate() {
+            let moves = operands(ops, "m");
+            assert_eq!(moves.len(), 1);
+            assert_close(&moves[0], &[0.0, mm(10.0 * (i + 1) as f32)]);
+        }
+    }
+}
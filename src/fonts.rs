@@ -0,0 +1,390 @@
+//! Font families the report text can be set in.
+//!
+//! A [`FontRegistry`] holds faces grouped by family, each with a weight and a
+//! style, and picks the closest face for a family, weight and style the way
+//! CSS font matching does, so a family registered without a bold face still
+//! sets bold text in its regular one. Faces are numbered in the order they
+//! are registered: a canvas given the registry's fonts in that order can draw
+//! the glyphs of every [`TextMeasurer`] the registry builds.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use owned_ttf_parser::name::name_id;
+use owned_ttf_parser::{Face, FaceParsingError};
+use printpdf::BuiltinFont;
+
+use crate::text::TextMeasurer;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The font file could not be read.
+    Io(io::Error),
+    /// The font data could not be parsed.
+    Font(FaceParsingError),
+    /// No face of this family is registered.
+    UnknownFamily(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Font(e) => write!(f, "invalid font: {}", e),
+            Error::UnknownFamily(family) => write!(f, "unknown font family {:?}", family),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Font(e) => Some(e),
+            Error::UnknownFamily(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<FaceParsingError> for Error {
+    fn from(e: FaceParsingError) -> Self {
+        Error::Font(e)
+    }
+}
+
+/// Stroke weight on the CSS / OpenType scale: 400 is regular, 700 bold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Weight(pub u16);
+
+impl Weight {
+    pub const LIGHT: Weight = Weight(300);
+    pub const REGULAR: Weight = Weight(400);
+    pub const MEDIUM: Weight = Weight(500);
+    pub const BOLD: Weight = Weight(700);
+}
+
+/// Upright or slanted. Oblique faces count as italic.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    #[default]
+    Normal,
+    Italic,
+}
+
+/// Where the outlines of a registered face come from.
+#[derive(Clone)]
+pub enum FontData {
+    /// A PDF builtin font, drawn by the viewer.
+    Builtin(BuiltinFont),
+    /// TrueType/OpenType data, embedded in the document.
+    TrueType(Vec<u8>),
+}
+
+/// One face of a family.
+#[derive(Clone)]
+pub struct RegisteredFace {
+    pub family: String,
+    pub weight: Weight,
+    pub style: Style,
+    pub data: FontData,
+}
+
+impl fmt::Debug for RegisteredFace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // the font data itself is not worth printing
+        let data = match &self.data {
+            FontData::Builtin(font) => format!("{:?}", font),
+            FontData::TrueType(bytes) => format!("{} bytes", bytes.len()),
+        };
+        f.debug_struct("RegisteredFace")
+            .field("family", &self.family)
+            .field("weight", &self.weight)
+            .field("style", &self.style)
+            .field("data", &data)
+            .finish()
+    }
+}
+
+/// Which faces a kind of text is set in: the best match in the first
+/// family, then in each of the others for characters it has no glyph for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontSelection {
+    pub families: Vec<String>,
+    pub weight: Weight,
+    pub style: Style,
+}
+
+impl FontSelection {
+    pub fn new(family: impl Into<String>, weight: Weight, style: Style) -> Self {
+        FontSelection {
+            families: vec![family.into()],
+            weight,
+            style,
+        }
+    }
+}
+
+/// Faces available to the report, by family.
+#[derive(Clone, Debug)]
+pub struct FontRegistry {
+    faces: Vec<RegisteredFace>,
+}
+
+/// The builtin Helvetica family, which needs nothing embedded.
+impl Default for FontRegistry {
+    fn default() -> Self {
+        let mut registry = FontRegistry::new();
+        for (font, weight, style) in [
+            (BuiltinFont::Helvetica, Weight::REGULAR, Style::Normal),
+            (BuiltinFont::HelveticaBold, Weight::BOLD, Style::Normal),
+            (
+                BuiltinFont::HelveticaOblique,
+                Weight::REGULAR,
+                Style::Italic,
+            ),
+            (
+                BuiltinFont::HelveticaBoldOblique,
+                Weight::BOLD,
+                Style::Italic,
+            ),
+        ] {
+            registry.faces.push(RegisteredFace {
+                family: "Helvetica".to_string(),
+                weight,
+                style,
+                data: FontData::Builtin(font),
+            });
+        }
+        registry
+    }
+}
+
+impl FontRegistry {
+    /// A registry with no faces at all.
+    pub fn new() -> Self {
+        FontRegistry { faces: Vec::new() }
+    }
+
+    /// Register the first face of a TrueType/OpenType font under the family,
+    /// weight and style its `name` and `OS/2` tables give. Returns its id.
+    pub fn add_font(&mut self, font: Vec<u8>) -> Result<usize, Error> {
+        let face = Face::parse(&font, 0)?;
+        let family = family_name(&face).unwrap_or_else(|| "Unnamed".to_string());
+        let weight = Weight(face.weight().to_number());
+        let style = if face.is_italic() || face.is_oblique() {
+            Style::Italic
+        } else {
+            Style::Normal
+        };
+        self.add_font_as(family, weight, style, font)
+    }
+
+    /// Register a font file, as [`FontRegistry::add_font`].
+    pub fn add_font_file(&mut self, path: impl AsRef<Path>) -> Result<usize, Error> {
+        self.add_font(std::fs::read(path)?)
+    }
+
+    /// Register the first face of a font under an explicit family, weight
+    /// and style, for fonts whose own tables are wrong or too specific
+    /// (say, a "Roboto Medium" family that should be Roboto at 500).
+    pub fn add_font_as(
+        &mut self,
+        family: impl Into<String>,
+        weight: Weight,
+        style: Style,
+        font: Vec<u8>,
+    ) -> Result<usize, Error> {
+        Face::parse(&font, 0)?;
+        self.faces.push(RegisteredFace {
+            family: family.into(),
+            weight,
+            style,
+            data: FontData::TrueType(font),
+        });
+        Ok(self.faces.len() - 1)
+    }
+
+    /// Every registered face; a face's id is its index.
+    pub fn faces(&self) -> &[RegisteredFace] {
+        &self.faces
+    }
+
+    pub fn has_family(&self, family: &str) -> bool {
+        self.faces
+            .iter()
+            .any(|face| face.family.eq_ignore_ascii_case(family))
+    }
+
+    /// Id of the face of `family` closest to `weight` and `style`.
+    ///
+    /// The style wins over the weight. Among weights, as in CSS: asking for
+    /// 400 to 500 tries heavier faces up to 500 first, then lighter ones,
+    /// then heavier ones; below 400 lighter ones come first and above 500
+    /// heavier ones do.
+    pub fn select(&self, family: &str, weight: Weight, style: Style) -> Option<usize> {
+        let in_family = || {
+            self.faces
+                .iter()
+                .enumerate()
+                .filter(|(_, face)| face.family.eq_ignore_ascii_case(family))
+        };
+        let same_style = in_family().any(|(_, face)| face.style == style);
+        let desired = weight.0;
+        in_family()
+            .filter(|(_, face)| !same_style || face.style == style)
+            .min_by_key(|(_, face)| weight_rank(desired, face.weight.0))
+            .map(|(id, _)| id)
+    }
+
+    /// Measurer for `selection`, with the faces of its fallback families
+    /// chained after the first, under their registry ids.
+    pub fn measurer(&self, selection: &FontSelection) -> Result<TextMeasurer, Error> {
+        let faces = selection
+            .families
+            .iter()
+            .map(|family| {
+                let id = self
+                    .select(family, selection.weight, selection.style)
+                    .ok_or_else(|| Error::UnknownFamily(family.clone()))?;
+                // only Helvetica is builtin, and fonts are parsed once
+                // already when registered
+                let measurer = match &self.faces[id].data {
+                    FontData::Builtin(font) => {
+                        TextMeasurer::builtin(*font).expect("builtin Helvetica face")
+                    }
+                    FontData::TrueType(bytes) => {
+                        TextMeasurer::new(bytes.clone()).expect("font checked when registered")
+                    }
+                };
+                Ok((id, measurer))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(TextMeasurer::with_ids(faces))
+    }
+}
+
+/// Sort key of a face of weight `candidate` when `desired` is asked for.
+fn weight_rank(desired: u16, candidate: u16) -> (u8, u16) {
+    let tier = if (400..=500).contains(&desired) {
+        if (desired..=500).contains(&candidate) {
+            0
+        } else if candidate < desired {
+            1
+        } else {
+            2
+        }
+    } else if candidate == desired || (candidate < desired) == (desired < 400) {
+        0
+    } else {
+        1
+    };
+    (tier, desired.abs_diff(candidate))
+}
+
+/// Typographic family name if the font has one, else its legacy family.
+fn family_name(face: &Face) -> Option<String> {
+    [name_id::TYPOGRAPHIC_FAMILY, name_id::FAMILY]
+        .iter()
+        .find_map(|&id| {
+            face.names()
+                .into_iter()
+                .filter(|name| name.name_id == id)
+                .find_map(|name| name.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTO: &[u8] = include_bytes!("../vendor/printpdf/assets/fonts/RobotoMedium.ttf");
+
+    #[test]
+    fn builtin_family_has_bold_and_italic() {
+        let fonts = FontRegistry::default();
+        let id = |weight, style| fonts.select("helvetica", weight, style).unwrap();
+        let builtin = |id: usize| match fonts.faces()[id].data {
+            FontData::Builtin(font) => font,
+            _ => panic!("not builtin"),
+        };
+        assert_eq!(
+            builtin(id(Weight::REGULAR, Style::Normal)),
+            BuiltinFont::Helvetica
+        );
+        assert_eq!(
+            builtin(id(Weight::BOLD, Style::Normal)),
+            BuiltinFont::HelveticaBold
+        );
+        assert_eq!(
+            builtin(id(Weight(900), Style::Italic)),
+            BuiltinFont::HelveticaBoldOblique
+        );
+        assert_eq!(
+            builtin(id(Weight::LIGHT, Style::Italic)),
+            BuiltinFont::HelveticaOblique
+        );
+        assert_eq!(fonts.select("Roboto", Weight::REGULAR, Style::Normal), None);
+    }
+
+    #[test]
+    fn weights_are_matched_as_in_css() {
+        let mut fonts = FontRegistry::new();
+        for weight in [300, 500, 700] {
+            fonts
+                .add_font_as("Roboto", Weight(weight), Style::Normal, ROBOTO.to_vec())
+                .unwrap();
+        }
+        let weight = |desired| {
+            let id = fonts.select("Roboto", Weight(desired), Style::Normal);
+            fonts.faces()[id.unwrap()].weight.0
+        };
+        assert_eq!(weight(400), 500);
+        assert_eq!(weight(600), 700);
+        assert_eq!(weight(200), 300);
+        assert_eq!(weight(900), 700);
+        // italic falls back to the upright faces
+        let id = fonts.select("Roboto", Weight::BOLD, Style::Italic);
+        assert_eq!(fonts.faces()[id.unwrap()].weight, Weight::BOLD);
+    }
+
+    #[test]
+    fn family_weight_and_style_come_from_the_font() {
+        let mut fonts = FontRegistry::default();
+        let id = fonts.add_font(ROBOTO.to_vec()).unwrap();
+        assert_eq!(id, 4);
+        let face = &fonts.faces()[id];
+        assert_eq!(face.weight, Weight::MEDIUM);
+        assert_eq!(face.style, Style::Normal);
+        assert!(fonts.has_family(&face.family.clone()));
+    }
+
+    #[test]
+    fn measurers_use_registry_ids() {
+        let mut fonts = FontRegistry::default();
+        fonts
+            .add_font_as("Roboto", Weight::MEDIUM, Style::Normal, ROBOTO.to_vec())
+            .unwrap();
+        let mut selection = FontSelection::new("Helvetica", Weight::BOLD, Style::Normal);
+        selection.families.push("Roboto".to_string());
+
+        let m = fonts.measurer(&selection).unwrap();
+        let ids: Vec<usize> = m.glyphs("AΔ").unwrap().iter().map(|g| g.font).collect();
+        assert_eq!(ids, [1, 4]);
+        // "b" is 611 thousandths of an em in Helvetica-Bold, 556 otherwise
+        let regular = TextMeasurer::helvetica().width("b", 10.0).unwrap();
+        assert!(m.width("b", 10.0).unwrap() > regular);
+
+        selection.families.push("Times".to_string());
+        assert!(matches!(
+            fonts.measurer(&selection),
+            Err(Error::UnknownFamily(family)) if family == "Times"
+        ));
+    }
+}
@@ -0,0 +1,943 @@
+//! Loading evaluations from the JSON intake format.
+//!
+//! The format is described by `schema/evaluation.schema.json`; a complete
+//! example lives in `examples/jane_doe.json`. Results are grouped per ear,
+//! and thresholds are keyed by frequency in Hz:
+//!
+//! ```json
+//! "right": {
+//!     "air": { "500": 35, "1000": { "level": 45, "masked": true } },
+//!     "bone": { "500": 30, "4000": { "level": 70, "no_response": true } },
+//!     "speech": { "srt": 40, "mcl": 75, "word_recognition": { "score": 88, "level": 80 } },
+//!     "tympanometry": { "peak_pressure": -20, "peak_compliance": 0.7, "ear_canal_volume": 1.2 },
+//!     "reflexes": [{ "frequency": 1000, "route": "ipsilateral", "level": 95 }]
+//! }
+//! ```
+//!
+//! Parsing is strict: unknown fields are rejected, and every value is checked
+//! against the report's ranges. Validation errors name the offending JSON
+//! path, e.g. `right.air[4000]: threshold 135 exceeds 120 dB HL grid`.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::model::{
+    is_test_frequency, Audiogram, Clinician, Date, EarImmittance, EarSpeech, EarThresholds,
+    Evaluation, Immittance, Modality, NoiseTest, Patient, PiPoint, Reflex, ReflexRoute, Speech,
+    SpeechInNoise, Stimulus, Threshold, Tympanogram, TympanogramSample, Validity, WordRecognition,
+    MAX_LEVEL, MIN_LEVEL,
+};
+
+/// Pressure range (daPa) accepted for tympanometry.
+const PRESSURE_RANGE: (f32, f32) = (-600.0, 400.0);
+
+/// Signal-to-noise ratio range (dB) accepted for speech-in-noise results.
+const SNR_RANGE: (f32, f32) = (-20.0, 40.0);
+
+/// Upper bound (ml) accepted for compliance and ear canal volume.
+const MAX_VOLUME: f32 = 10.0;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// Malformed JSON, wrong types or unknown fields.
+    Json(serde_json::Error),
+    /// Well-formed JSON whose values are out of range or inconsistent.
+    Invalid(Vec<ValidationError>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "{}", e),
+            Error::Json(e) => write!(f, "invalid evaluation JSON: {}", e),
+            Error::Invalid(errors) => {
+                write!(f, "invalid evaluation:")?;
+                for e in errors {
+                    write!(f, "\n  {}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+/// A value that failed validation, with the JSON path leading to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Read and validate an evaluation file.
+pub fn load(path: impl AsRef<Path>) -> Result<Evaluation, Error> {
+    let text = std::fs::read_to_string(path)?;
+    from_json(&text)
+}
+
+/// Parse and validate an evaluation from JSON text.
+pub fn from_json(text: &str) -> Result<Evaluation, Error> {
+    let raw: RawEvaluation = serde_json::from_str(text)?;
+    let mut v = Validator::default();
+    let eval = raw.validate(&mut v);
+    if v.errors.is_empty() {
+        Ok(eval)
+    } else {
+        Err(Error::Invalid(v.errors))
+    }
+}
+
+#[derive(Default)]
+struct Validator {
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.errors.push(ValidationError {
+            path: path.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn date(&mut self, path: &str, s: &str) -> Option<Date> {
+        let date = Date::parse(s);
+        if date.is_none() {
+            self.error(path, format!("invalid date {:?}, expected YYYY-MM-DD", s));
+        }
+        date
+    }
+
+    fn level(&mut self, path: &str, what: &str, level: i32) {
+        if level > MAX_LEVEL {
+            self.error(
+                path,
+                format!("{} {} exceeds {} dB HL grid", what, level, MAX_LEVEL),
+            );
+        } else if level < MIN_LEVEL {
+            self.error(
+                path,
+                format!("{} {} is below {} dB HL grid", what, level, MIN_LEVEL),
+            );
+        }
+    }
+
+    fn frequency(&mut self, path: &str, frequency: u32) {
+        if !is_test_frequency(frequency) {
+            self.error(
+                path,
+                format!(
+                    "frequency {} Hz is not an audiometric test frequency",
+                    frequency
+                ),
+            );
+        }
+    }
+
+    /// Whether `key`, parsed to `value`, is the first key of its map with
+    /// that value. Keys are only parsed after deserialization, so a key
+    /// given twice, or `"500"` and `"0500"`, reach here as two entries for
+    /// one point.
+    fn unique_key<T: PartialEq>(
+        &mut self,
+        path: &str,
+        key: &str,
+        value: T,
+        seen: &mut Vec<(T, String)>,
+    ) -> bool {
+        if let Some((_, first)) = seen.iter().find(|(seen, _)| *seen == value) {
+            self.error(path, format!("duplicate of key {:?}", first));
+            return false;
+        }
+        seen.push((value, key.to_string()));
+        true
+    }
+
+    fn range(&mut self, path: &str, what: &str, value: f32, min: f32, max: f32) {
+        if !(min..=max).contains(&value) {
+            self.error(
+                path,
+                format!("{} {} is outside {}..{}", what, value, min, max),
+            );
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEvaluation {
+    patient: RawPatient,
+    clinician: RawClinician,
+    date: String,
+    #[serde(default)]
+    place: String,
+    validity: Validity,
+    stimulus: Stimulus,
+    method: String,
+    #[serde(default)]
+    right: RawEar,
+    #[serde(default)]
+    left: RawEar,
+    #[serde(default)]
+    notes: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPatient {
+    first_name: String,
+    last_name: String,
+    date_of_birth: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawClinician {
+    name: String,
+    #[serde(default)]
+    credentials: String,
+    #[serde(default)]
+    licence: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEar {
+    #[serde(default)]
+    air: Entries<RawThreshold>,
+    #[serde(default)]
+    bone: Entries<RawThreshold>,
+    #[serde(default)]
+    speech: RawSpeech,
+    tympanometry: Option<RawTympanogram>,
+    #[serde(default)]
+    reflexes: Vec<RawReflex>,
+}
+
+/// Entries of a JSON object keyed by a number, in document order. Unlike a
+/// map, a key given twice is kept twice, for validation to reject rather
+/// than serde silently keeping the last value.
+struct Entries<T>(Vec<(String, T)>);
+
+impl<T> Default for Entries<T> {
+    fn default() -> Self {
+        Entries(Vec::new())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Entries<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for EntriesVisitor<T> {
+            type Value = Entries<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(PhantomData))
+    }
+}
+
+/// A threshold is either a bare level or an object with flags. Anything
+/// else is kept as the problems found in it, for validation to report at
+/// the threshold's path.
+enum RawThreshold {
+    Level(i32),
+    Detailed(RawThresholdDetail),
+    /// Field path below the threshold, empty for the threshold itself, and
+    /// message of each problem.
+    Invalid(Vec<(String, String)>),
+}
+
+struct RawThresholdDetail {
+    level: i32,
+    masked: bool,
+    no_response: bool,
+}
+
+impl RawThreshold {
+    fn invalid(field: &str, message: String) -> Self {
+        RawThreshold::Invalid(vec![(field.to_string(), message)])
+    }
+}
+
+/// `value` as a level in whole dB.
+fn whole_level(value: &serde_json::Value) -> Option<i32> {
+    value.as_i64().and_then(|level| i32::try_from(level).ok())
+}
+
+/// Fill `slot` with `parsed`, the field's `value` as the type `expected`.
+fn set_field<T>(
+    slot: &mut Option<T>,
+    parsed: Option<T>,
+    expected: &str,
+    value: &serde_json::Value,
+) -> Result<(), String> {
+    if slot.is_some() {
+        return Err("field given twice".to_string());
+    }
+    match parsed {
+        Some(parsed) => {
+            *slot = Some(parsed);
+            Ok(())
+        }
+        None => Err(format!("expected {}, found {}", expected, value)),
+    }
+}
+
+impl<'de> Deserialize<'de> for RawThreshold {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RawThresholdVisitor)
+    }
+}
+
+struct RawThresholdVisitor;
+
+impl<'de> Visitor<'de> for RawThresholdVisitor {
+    type Value = RawThreshold;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a level in dB HL or an object with a level")
+    }
+
+    fn visit_i64<E>(self, level: i64) -> Result<RawThreshold, E> {
+        Ok(match i32::try_from(level) {
+            Ok(level) => RawThreshold::Level(level),
+            Err(_) => RawThreshold::invalid("", format!("invalid level {}", level)),
+        })
+    }
+
+    fn visit_u64<E>(self, level: u64) -> Result<RawThreshold, E> {
+        Ok(match i32::try_from(level) {
+            Ok(level) => RawThreshold::Level(level),
+            Err(_) => RawThreshold::invalid("", format!("invalid level {}", level)),
+        })
+    }
+
+    fn visit_f64<E>(self, level: f64) -> Result<RawThreshold, E> {
+        let message = format!("level {} is not a whole number of dB", level);
+        Ok(RawThreshold::invalid("", message))
+    }
+
+    fn visit_str<E>(self, text: &str) -> Result<RawThreshold, E> {
+        let message = format!("expected a level or an object, found {:?}", text);
+        Ok(RawThreshold::invalid("", message))
+    }
+
+    fn visit_bool<E>(self, flag: bool) -> Result<RawThreshold, E> {
+        let message = format!("expected a level or an object, found {}", flag);
+        Ok(RawThreshold::invalid("", message))
+    }
+
+    fn visit_unit<E>(self) -> Result<RawThreshold, E> {
+        let message = "expected a level or an object, found null".to_string();
+        Ok(RawThreshold::invalid("", message))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RawThreshold, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        let message = "expected a level or an object, found an array".to_string();
+        Ok(RawThreshold::invalid("", message))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawThreshold, A::Error> {
+        let mut problems = Vec::new();
+        let mut level = None;
+        let mut masked = None;
+        let mut no_response = None;
+        while let Some((key, value)) = map.next_entry::<String, serde_json::Value>()? {
+            let set = match key.as_str() {
+                "level" => set_field(
+                    &mut level,
+                    whole_level(&value),
+                    "a level in whole dB",
+                    &value,
+                ),
+                "masked" => set_field(&mut masked, value.as_bool(), "true or false", &value),
+                "no_response" => {
+                    set_field(&mut no_response, value.as_bool(), "true or false", &value)
+                }
+                _ => Err("unknown field, expected level, masked or no_response".to_string()),
+            };
+            if let Err(message) = set {
+                problems.push((format!(".{}", key), message));
+            }
+        }
+        match level {
+            None if problems.is_empty() => {
+                Ok(RawThreshold::invalid("", "missing field level".to_string()))
+            }
+            Some(level) if problems.is_empty() => Ok(RawThreshold::Detailed(RawThresholdDetail {
+                level,
+                masked: masked.unwrap_or(false),
+                no_response: no_response.unwrap_or(false),
+            })),
+            _ => Ok(RawThreshold::Invalid(problems)),
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSpeech {
+    srt: Option<i32>,
+    sdt: Option<i32>,
+    mcl: Option<i32>,
+    ucl: Option<i32>,
+    word_recognition: Option<RawWordRecognition>,
+    /// Word-recognition scores keyed by presentation level in dB HL.
+    #[serde(default)]
+    performance_intensity: Entries<f32>,
+    in_noise: Option<RawSpeechInNoise>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawWordRecognition {
+    score: f32,
+    level: i32,
+    list: Option<String>,
+    masking: Option<i32>,
+    modality: Option<Modality>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSpeechInNoise {
+    test: NoiseTest,
+    snr50: Option<f32>,
+    snr_loss: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTympanogram {
+    peak_pressure: f32,
+    peak_compliance: f32,
+    ear_canal_volume: f32,
+    gradient: Option<f32>,
+    /// Compliance in ml keyed by pressure in daPa.
+    #[serde(default)]
+    samples: Entries<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawReflex {
+    frequency: u32,
+    route: ReflexRoute,
+    level: Option<i32>,
+}
+
+impl RawEvaluation {
+    fn validate(self, v: &mut Validator) -> Evaluation {
+        let date = v.date("date", &self.date);
+        let date_of_birth = v.date("patient.date_of_birth", &self.patient.date_of_birth);
+        if let (Some(date), Some(date_of_birth)) = (date, date_of_birth) {
+            if date_of_birth > date {
+                v.error(
+                    "patient.date_of_birth",
+                    "date of birth is after the evaluation date",
+                );
+            }
+        }
+        // Placeholder for unparseable dates; the error above prevents it
+        // from ever reaching the caller.
+        let date = date.unwrap_or(Date::new(0, 1, 1));
+        let date_of_birth = date_of_birth.unwrap_or(Date::new(0, 1, 1));
+        if self.clinician.name.trim().is_empty() {
+            v.error("clinician.name", "must not be empty");
+        }
+
+        let (right_thresholds, right_speech, right_immittance) = self.right.validate(v, "right");
+        let (left_thresholds, left_speech, left_immittance) = self.left.validate(v, "left");
+
+        Evaluation {
+            patient: Patient {
+                first_name: self.patient.first_name,
+                last_name: self.patient.last_name,
+                date_of_birth,
+            },
+            clinician: Clinician {
+                name: self.clinician.name,
+                credentials: self.clinician.credentials,
+                licence: self.clinician.licence,
+            },
+            date,
+            place: self.place,
+            validity: self.validity,
+            stimulus: self.stimulus,
+            method: self.method,
+            audiogram: Audiogram {
+                right: right_thresholds,
+                left: left_thresholds,
+            },
+            speech: Speech {
+                right: right_speech,
+                left: left_speech,
+            },
+            immittance: Immittance {
+                right: right_immittance,
+                left: left_immittance,
+            },
+            notes: self.notes,
+        }
+    }
+}
+
+impl RawEar {
+    fn validate(self, v: &mut Validator, ear: &str) -> (EarThresholds, EarSpeech, EarImmittance) {
+        let thresholds = EarThresholds {
+            air: validate_thresholds(v, &format!("{}.air", ear), self.air),
+            bone: validate_thresholds(v, &format!("{}.bone", ear), self.bone),
+        };
+
+        let mut speech_level = |name: &str, label: &str, level: Option<i32>| {
+            level.inspect(|level| v.level(&format!("{}.speech.{}", ear, name), label, *level))
+        };
+        let mut speech = EarSpeech {
+            srt: speech_level("srt", "SRT", self.speech.srt),
+            sdt: speech_level("sdt", "SDT", self.speech.sdt),
+            mcl: speech_level("mcl", "MCL", self.speech.mcl),
+            ucl: speech_level("ucl", "UCL", self.speech.ucl),
+            word_recognition: None,
+            performance_intensity: Vec::new(),
+            in_noise: None,
+        };
+        if let (Some(mcl), Some(ucl)) = (speech.mcl, speech.ucl) {
+            if mcl > ucl {
+                v.error(
+                    &format!("{}.speech.mcl", ear),
+                    format!("MCL {} is above UCL {}", mcl, ucl),
+                );
+            }
+        }
+        speech.word_recognition = self.speech.word_recognition.map(|wr| {
+            let path = format!("{}.speech.word_recognition", ear);
+            v.range(&format!("{}.score", path), "score", wr.score, 0.0, 100.0);
+            v.level(&format!("{}.level", path), "level", wr.level);
+            if let Some(masking) = wr.masking {
+                v.level(&format!("{}.masking", path), "masking", masking);
+            }
+            WordRecognition {
+                score: wr.score,
+                level: wr.level,
+                list: wr.list.filter(|list| !list.trim().is_empty()),
+                masking: wr.masking,
+                modality: wr.modality,
+            }
+        });
+        let mut seen = Vec::new();
+        for (key, score) in self.speech.performance_intensity.0 {
+            let path = format!("{}.speech.performance_intensity[{}]", ear, key);
+            let Ok(level) = key.parse::<i32>() else {
+                v.error(&path, format!("invalid level {:?}", key));
+                continue;
+            };
+            if !v.unique_key(&path, &key, level, &mut seen) {
+                continue;
+            }
+            v.level(&path, "level", level);
+            v.range(&path, "score", score, 0.0, 100.0);
+            speech.performance_intensity.push(PiPoint { level, score });
+        }
+        speech.performance_intensity.sort_by_key(|p| p.level);
+        speech.in_noise = self.speech.in_noise.map(|sin| {
+            let path = format!("{}.speech.in_noise", ear);
+            let (min, max) = SNR_RANGE;
+            if let Some(snr50) = sin.snr50 {
+                v.range(&format!("{}.snr50", path), "SNR-50", snr50, min, max);
+            }
+            if let Some(snr_loss) = sin.snr_loss {
+                v.range(
+                    &format!("{}.snr_loss", path),
+                    "SNR loss",
+                    snr_loss,
+                    min,
+                    max,
+                );
+            }
+            SpeechInNoise {
+                test: sin.test,
+                snr50: sin.snr50,
+                snr_loss: sin.snr_loss,
+            }
+        });
+
+        let tympanogram = self.tympanometry.map(|t| {
+            let path = format!("{}.tympanometry", ear);
+            let (min, max) = PRESSURE_RANGE;
+            v.range(
+                &format!("{}.peak_pressure", path),
+                "pressure",
+                t.peak_pressure,
+                min,
+                max,
+            );
+            v.range(
+                &format!("{}.peak_compliance", path),
+                "compliance",
+                t.peak_compliance,
+                0.0,
+                MAX_VOLUME,
+            );
+            v.range(
+                &format!("{}.ear_canal_volume", path),
+                "volume",
+                t.ear_canal_volume,
+                0.0,
+                MAX_VOLUME,
+            );
+            if let Some(gradient) = t.gradient {
+                v.range(
+                    &format!("{}.gradient", path),
+                    "gradient",
+                    gradient,
+                    0.0,
+                    max - min,
+                );
+            }
+            let mut samples = Vec::with_capacity(t.samples.0.len());
+            let mut seen = Vec::new();
+            for (key, compliance) in t.samples.0 {
+                let path = format!("{}.samples[{}]", path, key);
+                let Ok(pressure) = key.parse::<f32>() else {
+                    v.error(&path, format!("invalid pressure {:?}", key));
+                    continue;
+                };
+                if !v.unique_key(&path, &key, pressure, &mut seen) {
+                    continue;
+                }
+                v.range(&path, "pressure", pressure, min, max);
+                v.range(&path, "compliance", compliance, 0.0, MAX_VOLUME);
+                samples.push(TympanogramSample {
+                    pressure,
+                    compliance,
+                });
+            }
+            samples.sort_by(|a, b| a.pressure.total_cmp(&b.pressure));
+            Tympanogram {
+                peak_pressure: t.peak_pressure,
+                peak_compliance: t.peak_compliance,
+                ear_canal_volume: t.ear_canal_volume,
+                gradient: t.gradient,
+                samples,
+            }
+        });
+
+        let reflexes = self
+            .reflexes
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let path = format!("{}.reflexes[{}]", ear, i);
+                v.frequency(&format!("{}.frequency", path), r.frequency);
+                if let Some(level) = r.level {
+                    v.level(&format!("{}.level", path), "level", level);
+                }
+                Reflex {
+                    frequency: r.frequency,
+                    route: r.route,
+                    level: r.level,
+                }
+            })
+            .collect();
+
+        let immittance = EarImmittance {
+            tympanogram,
+            reflexes,
+        };
+        (thresholds, speech, immittance)
+    }
+}
+
+fn validate_thresholds(
+    v: &mut Validator,
+    path: &str,
+    raw: Entries<RawThreshold>,
+) -> Vec<Threshold> {
+    let mut thresholds = Vec::with_capacity(raw.0.len());
+    let mut seen = Vec::new();
+    for (key, value) in raw.0 {
+        let path = format!("{}[{}]", path, key);
+        let Ok(frequency) = key.parse::<u32>() else {
+            v.error(&path, format!("invalid frequency {:?}", key));
+            continue;
+        };
+        if !v.unique_key(&path, &key, frequency, &mut seen) {
+            continue;
+        }
+        v.frequency(&path, frequency);
+        let threshold = match value {
+            RawThreshold::Level(level) => Threshold {
+                frequency,
+                level,
+                masked: false,
+                no_response: false,
+            },
+            RawThreshold::Detailed(d) => Threshold {
+                frequency,
+                level: d.level,
+                masked: d.masked,
+                no_response: d.no_response,
+            },
+            RawThreshold::Invalid(problems) => {
+                for (field, message) in problems {
+                    v.error(&format!("{}{}", path, field), message);
+                }
+                continue;
+            }
+        };
+        v.level(&path, "threshold", threshold.level);
+        thresholds.push(threshold);
+    }
+    thresholds.sort_by_key(|t| t.frequency);
+    thresholds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal valid evaluation with `right` spliced in as the right ear.
+    fn evaluation(date: &str, right: &str) -> String {
+        format!(
+            r#"{{
+                "patient": {{ "first_name": "A", "last_name": "B", "date_of_birth": "1980-01-01" }},
+                "clinician": {{ "name": "C" }},
+                "date": "{}",
+                "validity": "good",
+                "stimulus": "pure_tone",
+                "method": "Hughson-Westlake",
+                "right": {}
+            }}"#,
+            date, right
+        )
+    }
+
+    /// The `(path, message)` pairs of a rejected evaluation.
+    fn errors(text: &str) -> Vec<(String, String)> {
+        match from_json(text) {
+            Err(Error::Invalid(errors)) => {
+                errors.into_iter().map(|e| (e.path, e.message)).collect()
+            }
+            Err(e) => panic!("expected validation errors, got {}", e),
+            Ok(_) => panic!("expected validation errors"),
+        }
+    }
+
+    fn paths(text: &str) -> Vec<String> {
+        errors(text).into_iter().map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn valid_evaluation_loads() {
+        let eval = from_json(&evaluation(
+            "2025-06-27",
+            r#"{ "air": { "1000": 45, "500": { "level": 35, "masked": true } } }"#,
+        ))
+        .unwrap();
+        let air = &eval.audiogram.right.air;
+        assert_eq!(air.len(), 2);
+        assert_eq!(
+            (air[0].frequency, air[0].level, air[0].masked),
+            (500, 35, true)
+        );
+        assert_eq!(air[1].frequency, 1000);
+    }
+
+    #[test]
+    fn example_is_valid() {
+        from_json(include_str!("../examples/jane_doe.json")).unwrap();
+    }
+
+    #[test]
+    fn out_of_range_levels_name_their_path() {
+        let errors = errors(&evaluation(
+            "2025-06-27",
+            r#"{ "air": { "4000": 135 }, "bone": { "500": -20 } }"#,
+        ));
+        assert_eq!(
+            errors[0],
+            (
+                "right.air[4000]".to_string(),
+                "threshold 135 exceeds 120 dB HL grid".to_string()
+            )
+        );
+        assert_eq!(errors[1].0, "right.bone[500]");
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn unknown_frequencies_and_keys_are_rejected() {
+        assert_eq!(
+            paths(&evaluation(
+                "2025-06-27",
+                r#"{ "air": { "1100": 40, "high": 40 } }"#
+            )),
+            ["right.air[1100]", "right.air[high]"]
+        );
+    }
+
+    #[test]
+    fn keys_naming_the_same_point_are_rejected() {
+        let errors = errors(&evaluation(
+            "2025-06-27",
+            r#"{
+                "air": { "500": 30, "0500": 35 },
+                "speech": { "performance_intensity": { "60": 80, "+60": 84 } },
+                "tympanometry": {
+                    "peak_pressure": 0, "peak_compliance": 0.5, "ear_canal_volume": 1.0,
+                    "samples": { "-100": 0.3, "-100.0": 0.3 }
+                }
+            }"#,
+        ));
+        let paths: Vec<_> = errors.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "right.air[0500]",
+                "right.speech.performance_intensity[+60]",
+                "right.tympanometry.samples[-100.0]"
+            ]
+        );
+        assert_eq!(errors[0].1, "duplicate of key \"500\"");
+    }
+
+    #[test]
+    fn repeated_keys_are_rejected() {
+        let errors = errors(&evaluation(
+            "2025-06-27",
+            r#"{
+                "air": { "1000": 40, "2000": 50, "1000": 60 },
+                "bone": { "1000": { "level": 30 }, "1000": 35 },
+                "speech": { "performance_intensity": { "60": 80, "60": 84 } },
+                "tympanometry": {
+                    "peak_pressure": 0, "peak_compliance": 0.5, "ear_canal_volume": 1.0,
+                    "samples": { "-100": 0.3, "-100": 0.4 }
+                }
+            }"#,
+        ));
+        assert_eq!(
+            errors[0],
+            (
+                "right.air[1000]".to_string(),
+                "duplicate of key \"1000\"".to_string()
+            )
+        );
+        let paths: Vec<_> = errors.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "right.air[1000]",
+                "right.bone[1000]",
+                "right.speech.performance_intensity[60]",
+                "right.tympanometry.samples[-100]"
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_dates_are_rejected() {
+        assert_eq!(paths(&evaluation("2025-02-31", "{}")), ["date"]);
+        assert_eq!(paths(&evaluation("2025-04-31", "{}")), ["date"]);
+        assert_eq!(paths(&evaluation("27/06/2025", "{}")), ["date"]);
+        assert_eq!(
+            paths(&evaluation("1979-12-31", "{}")),
+            ["patient.date_of_birth"]
+        );
+        assert!(from_json(&evaluation("2024-02-29", "{}")).is_ok());
+    }
+
+    #[test]
+    fn malformed_thresholds_name_their_field() {
+        let errors = errors(&evaluation(
+            "2025-06-27",
+            r#"{
+                "air": {
+                    "500": { "levle": 40 },
+                    "1000": { "level": "x", "masked": 1 },
+                    "2000": { "masked": true },
+                    "4000": "40",
+                    "8000": 40.5
+                },
+                "bone": { "1000": { "level": 30, "level": 35 } }
+            }"#,
+        ));
+        let expected = [
+            (
+                "right.air[500].levle",
+                "unknown field, expected level, masked or no_response",
+            ),
+            (
+                "right.air[1000].level",
+                "expected a level in whole dB, found \"x\"",
+            ),
+            ("right.air[1000].masked", "expected true or false, found 1"),
+            ("right.air[2000]", "missing field level"),
+            (
+                "right.air[4000]",
+                "expected a level or an object, found \"40\"",
+            ),
+            ("right.air[8000]", "level 40.5 is not a whole number of dB"),
+            ("right.bone[1000].level", "field given twice"),
+        ];
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|&(path, message)| (path.to_string(), message.to_string()))
+            .collect();
+        assert_eq!(errors, expected);
+    }
+
+    #[test]
+    fn unknown_fields_are_json_errors() {
+        let text = evaluation("2025-06-27", r#"{ "air": {}, "bonne": {} }"#);
+        match from_json(&text) {
+            Err(Error::Json(e)) => assert!(e.to_string().contains("unknown field `bonne`")),
+            _ => panic!("expected a JSON error"),
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Fixed report wording, per language.
+
+use std::str::FromStr;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    Fr,
+    En,
+}
+
+impl Lang {
+    pub fn labels(self) -> &'static Labels {
+        match self {
+            Lang::Fr => &FR,
+            Lang::En => &EN,
+        }
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fr" => Ok(Lang::Fr),
+            "en" => Ok(Lang::En),
+            _ => Err(format!("unknown language {:?} (expected fr or en)", s)),
+        }
+    }
+}
+
+/// Every piece of fixed text printed on the report.
+pub struct Labels {
+    pub evaluation_date: &'static str,
+    pub evaluation_place: &'static str,
+    pub years_old: &'static str,
+    pub profession: &'static str,
+    /// Profession as written under the signature, before the licence number.
+    pub signature_title: &'static str,
+    pub tonal_audiometry: &'static str,
+    pub right_ear: &'static str,
+    pub left_ear: &'static str,
+    pub air_conduction: &'static str,
+    pub bone_conduction: &'static str,
+    pub unmasked: &'static str,
+    pub masked: &'static str,
+    pub validity: &'static str,
+    pub validity_good: &'static str,
+    pub validity_fair: &'static str,
+    pub validity_poor: &'static str,
+    pub stimuli: &'static str,
+    pub pure_tones: &'static str,
+    pub warble_tones: &'static str,
+    pub method: &'static str,
+    pub pure_tone_averages: &'static str,
+    pub pta3: &'static str,
+    pub pta4: &'static str,
+    pub fletcher: &'static str,
+    pub speech_audiometry: &'static str,
+    pub speech_thresholds: &'static str,
+    pub srt: &'static str,
+    pub sdt: &'static str,
+    pub mcl: &'static str,
+    pub ucl: &'static str,
+    pub word_recognition: &'static str,
+    pub score: &'static str,
+    pub level: &'static str,
+    pub list: &'static str,
+    pub masking: &'static str,
+    /// How the words were presented: live voice or recorded.
+    pub modality: &'static str,
+    /// Monitored live voice.
+    pub live_voice: &'static str,
+    pub recorded: &'static str,
+    /// Abbreviated rollover index of the PI-PB function.
+    pub rollover_index: &'static str,
+    pub speech_in_noise: &'static str,
+    pub noise_test: &'static str,
+    pub snr50: &'static str,
+    pub snr_loss: &'static str,
+    pub snr_loss_category: &'static str,
+    pub snr_normal: &'static str,
+    pub snr_mild: &'static str,
+    pub snr_moderate: &'static str,
+    pub snr_severe: &'static str,
+    pub immittance: &'static str,
+    pub tympanometry: &'static str,
+    pub pressure: &'static str,
+    pub compliance: &'static str,
+    pub volume: &'static str,
+    /// Tympanometric width.
+    pub gradient: &'static str,
+    /// Precedes the Jerger type, e.g. "Type A".
+    pub tympanogram_type: &'static str,
+    /// In place of the peak values of a flat tympanogram.
+    pub no_peak: &'static str,
+    pub acoustic_reflex: &'static str,
+    pub ipsilateral: &'static str,
+    pub contralateral: &'static str,
+    pub no_response: &'static str,
+    /// Spelled-out `no_response`, for the audiogram legend.
+    pub no_response_legend: &'static str,
+    pub notes: &'static str,
+    /// Heading of the extra page notes continue on.
+    pub notes_continued: &'static str,
+    pub member: &'static str,
+}
+
+pub static FR: Labels = Labels {
+    evaluation_date: "Date de l'évaluation",
+    evaluation_place: "Lieu de l'évaluation",
+    years_old: "ans",
+    profession: "AUDIOLOGISTE",
+    signature_title: "Audiologiste",
+    tonal_audiometry: "AUDIOMÉTRIE TONALE",
+    right_ear: "OREILLE DROITE",
+    left_ear: "OREILLE GAUCHE",
+    air_conduction: "SEUIL AÉRIEN",
+    bone_conduction: "SEUIL OSSEUX",
+    unmasked: "Non masqué",
+    masked: "Masqué",
+    validity: "VALIDITÉ",
+    validity_good: "Bonne",
+    validity_fair: "Moyenne",
+    validity_poor: "Nulle",
+    stimuli: "STIMULI",
+    pure_tones: "Sons purs",
+    warble_tones: "Sons hululés",
+    method: "MÉTHODE D'ÉVALUATION",
+    pure_tone_averages: "MOYENNES DES SONS PURS - dB HL",
+    pta3: "MSP3",
+    pta4: "MSP4",
+    fletcher: "FLCH",
+    speech_audiometry: "AUDIOMÉTRIE VOCALE",
+    speech_thresholds: "SEUILS VOCAUX - dB HL",
+    srt: "SRP",
+    sdt: "SDP",
+    mcl: "NCO",
+    ucl: "SI",
+    word_recognition: "IDENTIFICATION PAROLE",
+    score: "Résultat %",
+    level: "Niveau dB HL",
+    list: "Liste",
+    masking: "Masque dB EM",
+    modality: "Présentation",
+    live_voice: "Voix vive",
+    recorded: "Enregistré",
+    rollover_index: "IR",
+    speech_in_noise: "PAROLE DANS LE BRUIT",
+    noise_test: "Test",
+    snr50: "RSB-50 dB",
+    snr_loss: "Perte RSB dB",
+    snr_loss_category: "Catégorie",
+    snr_normal: "Normale",
+    snr_mild: "Légère",
+    snr_moderate: "Modérée",
+    snr_severe: "Sévère",
+    immittance: "IMMITANCEMÉTRIE",
+    tympanometry: "TYMPANOMÉTRIE",
+    pressure: "Pression",
+    compliance: "Compliance",
+    volume: "Volume",
+    gradient: "Gradient",
+    tympanogram_type: "Type",
+    no_peak: "Pas de pic",
+    acoustic_reflex: "RÉFLEXE STAPÉDIEN - dB",
+    ipsilateral: "Ipsi",
+    contralateral: "Contra",
+    no_response: "AR",
+    no_response_legend: "Absence de réponse",
+    notes: "Notes",
+    notes_continued: "Notes (suite)",
+    member: "MEMBRE",
+};
+
+pub static EN: Labels = Labels {
+    evaluation_date: "Evaluation date",
+    evaluation_place: "Evaluation place",
+    years_old: "years old",
+    profession: "AUDIOLOGIST",
+    signature_title: "Audiologist",
+    tonal_audiometry: "PURE-TONE AUDIOMETRY",
+    right_ear: "RIGHT EAR",
+    left_ear: "LEFT EAR",
+    air_conduction: "AIR CONDUCTION",
+    bone_conduction: "BONE CONDUCTION",
+    unmasked: "Unmasked",
+    masked: "Masked",
+    validity: "VALIDITY",
+    validity_good: "Good",
+    validity_fair: "Fair",
+    validity_poor: "Poor",
+    stimuli: "STIMULI",
+    pure_tones: "Pure tones",
+    warble_tones: "Warble tones",
+    method: "EVALUATION METHOD",
+    pure_tone_averages: "PURE-TONE AVERAGES - dB HL",
+    pta3: "PTA3",
+    pta4: "PTA4",
+    fletcher: "FLCH",
+    speech_audiometry: "SPEECH AUDIOMETRY",
+    speech_thresholds: "SPEECH THRESHOLDS - dB HL",
+    srt: "SRT",
+    sdt: "SDT",
+    mcl: "MCL",
+    ucl: "UCL",
+    word_recognition: "WORD RECOGNITION",
+    score: "Score %",
+    level: "Level dB HL",
+    list: "List",
+    masking: "Masking dB EM",
+    modality: "Presentation",
+    live_voice: "Live voice",
+    recorded: "Recorded",
+    rollover_index: "RI",
+    speech_in_noise: "SPEECH IN NOISE",
+    noise_test: "Test",
+    snr50: "SNR-50 dB",
+    snr_loss: "SNR loss dB",
+    snr_loss_category: "Category",
+    snr_normal: "Normal",
+    snr_mild: "Mild",
+    snr_moderate: "Moderate",
+    snr_severe: "Severe",
+    immittance: "IMMITTANCE",
+    tympanometry: "TYMPANOMETRY",
+    pressure: "Pressure",
+    compliance: "Compliance",
+    volume: "Volume",
+    gradient: "Gradient",
+    tympanogram_type: "Type",
+    no_peak: "No peak",
+    acoustic_reflex: "ACOUSTIC REFLEX - dB",
+    ipsilateral: "Ipsi",
+    contralateral: "Contra",
+    no_response: "NR",
+    no_response_legend: "No response",
+    notes: "Notes",
+    notes_continued: "Notes (continued)",
+    member: "MEMBER",
+};
@@ -0,0 +1,10 @@
+pub mod canvas;
+pub mod fonts;
+pub mod input;
+pub mod labels;
+pub mod model;
+pub mod report;
+pub mod shapes;
+pub mod subset;
+pub mod text;
+pub mod widgets;
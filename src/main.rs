@@ -1,240 +1,406 @@
-use printpdf::*;
-use owned_ttf_parser::{AsFaceRef, OwnedFace};
-use std::{fs::File, io::BufWriter};
-
-mod shapes;
-use shapes::{draw_symbol, Symbol, SymbolColor};
-
-fn main() {
-    /* ── create a Letter page ─────────────────────────────────────────── */
-    let (doc, page, layer) = PdfDocument::new("Text‑input demo", Mm(215.9), Mm(279.4), "Layer 1");
-    let layer = doc.get_page(page).get_layer(layer);
-
-    /* ── geometry parameters ─────────────────────────────────────────── */
-    let rect_w = Mm(80.0); // width  80 mm
-    let rect_h = Mm(15.0); // height 15 mm
-    let origin = (Mm(65.0), Mm(140.0)); // lower‑left corner on page
-
-    /* ── draw text input outline with curved sides ───────────────────── */
-    draw_curved_sides_rect(&layer, origin, rect_w, rect_h);
-
-    // draw a rounded rectangle below the pill
-    let rounded_rect_origin = (Mm(origin.0 .0), Mm(origin.1 .0 - 25.0));
-    let rounded_rect_w = rect_w;
-    let rounded_rect_h = Mm(20.0);
-    let corner_radius = Mm(4.0);
-    draw_rounded_rect(&layer, rounded_rect_origin, rounded_rect_w, rounded_rect_h, corner_radius);
-
-    /* ── add centred text ────────────────────────────────────────────── */
-    // load an external font so we can query exact glyph metrics
-    let font_bytes = include_bytes!("../vendor/printpdf/assets/fonts/RobotoMedium.ttf");
-    let mut reader = std::io::Cursor::new(font_bytes.as_ref());
-    let font = doc.add_external_font(&mut reader).unwrap();
-
-    let caption = "Jane Doe";
-    let font_size_pt: f32 = 10.0;
-
-    // helper for converting between points and millimetres
-    let pt_to_mm = |pt: f32| pt * 25.4 / 72.0;
-
-    // compute exact text width using glyph metrics
-    let face = OwnedFace::from_vec(font_bytes.to_vec(), 0).unwrap();
-    let units_per_em = face.as_face_ref().units_per_em() as f32;
-    let mut width_units = 0u32;
-    for ch in caption.chars() {
-        if let Some(gid) = face.as_face_ref().glyph_index(ch) {
-            if let Some(adv) = face.as_face_ref().glyph_hor_advance(gid) {
-                width_units += adv as u32;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use svg_report::fonts::{self, FontRegistry};
+use svg_report::input;
+use svg_report::report::{self, ReportOptions};
+
+const USAGE: &str = "\
+Usage: svg_report render <evaluation.json> -o <output.pdf|output.svg> [options]
+
+Options:
+  -o, --output <path>     report to write, as SVG if the name ends in .svg
+                          and as PDF otherwise
+      --lang <fr|en>      report language (default: fr)
+      --paper <a4|letter> paper size (default: a4)
+      --font <path>       TrueType font to set the report in; repeat to add
+                          the bold or italic faces of its family
+      --fallback-font <path>
+                          TrueType font for characters the main font lacks;
+                          repeat to try several in order
+      --text-family <name>
+                          font family of the body text (default: the family
+                          of the first --font, or Helvetica)
+      --title-family <name>
+                          font family of the section titles, set in bold
+                          (default: the body text family)
+      --full-fonts        embed --font files whole instead of only the
+                          glyphs the report uses
+      --inter-octave      draw dashed 750/1500/3000/6000 Hz columns
+      --colors <scheme>   asha, mono or colorblind (default: asha)
+      --legend <corner>   upper-left, upper-right, lower-left, lower-right
+                          or none (default: lower-left)
+      --notes-overflow <mode>
+                          notes too long for the footer: shrink, ellipsis
+                          or next-page (default: next-page)
+  -h, --help              print this help
+
+Exit codes:
+  0   report written
+  64  invalid command line
+  65  invalid evaluation or font file, or text the font cannot show
+  66  evaluation or font file cannot be read
+  70  report could not be laid out
+  73  output file cannot be written";
+
+// Exit codes follow sysexits.h
+const EX_USAGE: u8 = 64;
+const EX_DATAERR: u8 = 65;
+const EX_NOINPUT: u8 = 66;
+const EX_SOFTWARE: u8 = 70;
+const EX_CANTCREAT: u8 = 73;
+
+/// A failure, with the exit code it maps to.
+struct CliError {
+    code: u8,
+    message: String,
+}
+
+impl CliError {
+    fn new(code: u8, message: impl Into<String>) -> Self {
+        CliError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+struct RenderArgs {
+    input: PathBuf,
+    output: PathBuf,
+    fonts: Vec<PathBuf>,
+    fallback_fonts: Vec<PathBuf>,
+    text_family: Option<String>,
+    title_family: Option<String>,
+    options: ReportOptions,
+}
+
+enum Command {
+    Help,
+    // boxed: the options dwarf the other variants
+    Render(Box<RenderArgs>),
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("svg_report: {}", e);
+            if e.code == EX_USAGE {
+                eprintln!("\n{}", USAGE);
             }
+            ExitCode::from(e.code)
         }
     }
-    let ascent = face.as_face_ref().ascender() as f32;
-    let descent = face.as_face_ref().descender() as f32;
-    let width_pt = width_units as f32 / units_per_em * font_size_pt;
-    let height_pt = (ascent - descent) / units_per_em * font_size_pt;
-    let descent_pt = -descent / units_per_em * font_size_pt;
-    let text_w_mm = pt_to_mm(width_pt);
-    let text_h_mm = pt_to_mm(height_pt);
-    let descent_mm = pt_to_mm(descent_pt);
-
-    // centre inside the rounded rectangle
-    let (x0, y0) = rounded_rect_origin;
-    let text_x = x0.0 + (rounded_rect_w.0 - text_w_mm) * 0.5;
-    let baseline_y = y0.0 + (rounded_rect_h.0 - text_h_mm) * 0.5 + descent_mm;
-
-    layer.use_text(caption, font_size_pt, Mm(text_x), Mm(baseline_y), &font);
-
-    // draw various audiogram symbols below
-    let sym_font = doc.add_builtin_font(BuiltinFont::Helvetica).unwrap();
-    let mut sx = Mm(origin.0 .0);
-    let sy = Mm(origin.1 .0 - 50.0);
-    let step = Mm(6.0);
-    let symbols = [
-        Symbol::Square,
-        Symbol::SquareFilled,
-        Symbol::Triangle,
-        Symbol::TriangleFilled,
-        Symbol::Circle,
-        Symbol::CircleFilled,
-        Symbol::S,
-        Symbol::SFilled,
-        Symbol::U,
-        Symbol::UFilled,
-        Symbol::X,
-        Symbol::XFilled,
-        Symbol::A,
-        Symbol::AFilled,
-        Symbol::Greater,
-        Symbol::GreaterFilled,
-        Symbol::Less,
-        Symbol::LessFilled,
-        Symbol::LeftBracket,
-        Symbol::LeftBracketFilled,
-        Symbol::RightBracket,
-        Symbol::RightBracketFilled,
-        Symbol::Star,
-        Symbol::StarFilled,
-        Symbol::ArrowDownRight,
-        Symbol::ArrowDownRightFilled,
-        Symbol::ArrowDownLeft,
-        Symbol::ArrowDownLeftFilled,
-        Symbol::VT,
-        Symbol::VTFilled,
-    ];
-    for (i, sym) in symbols.iter().enumerate() {
-        let color = if i % 2 == 0 { SymbolColor::Red } else { SymbolColor::Blue };
-        draw_symbol(&layer, &sym_font, *sym, (sx, sy), 10.0, color);
-        sx = Mm(sx.0 + step.0);
-    }
-
-    /* ── save ─────────────────────────────────────────────────────────── */
-    doc.save(&mut BufWriter::new(
-        File::create("text_input_demo.pdf").unwrap(),
-    ))
-    .unwrap();
 }
 
-/* --------------------------------------------------------------------- */
-/* helper: draw a capsule-like rectangle with curved left and right sides */
-fn draw_curved_sides_rect(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    w: Mm,
-    h: Mm,
-) {
-    use printpdf::{Line, Point};
-
-    const C: f32 = 0.55191505; // bezier approximation constant
-
-    let (x0, y0) = origin;
-    let (w, h) = (w.0, h.0);
-    let r = h / 2.0; // radius for the side curves
-    let c = r * C;
-
-    let pts = vec![
-        // start at bottom left after left curve
-        (Point::new(Mm(x0.0 + r), Mm(y0.0)), false),
-        // bottom edge
-        (Point::new(Mm(x0.0 + w - r), Mm(y0.0)), false),
-
-        // right side - bottom quarter
-        (Point::new(Mm(x0.0 + w - r), Mm(y0.0)), true),
-        (Point::new(Mm(x0.0 + w - r + c), Mm(y0.0)), true),
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + r - c)), true),
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + r)), false),
-
-        // right side - top quarter
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + r)), true),
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + r + c)), true),
-        (Point::new(Mm(x0.0 + w - r + c), Mm(y0.0 + h)), true),
-        (Point::new(Mm(x0.0 + w - r), Mm(y0.0 + h)), false),
-
-        // top edge
-        (Point::new(Mm(x0.0 + r), Mm(y0.0 + h)), false),
-
-        // left side - top quarter
-        (Point::new(Mm(x0.0 + r), Mm(y0.0 + h)), true),
-        (Point::new(Mm(x0.0 + r - c), Mm(y0.0 + h)), true),
-        (Point::new(Mm(x0.0), Mm(y0.0 + r + c)), true),
-        (Point::new(Mm(x0.0), Mm(y0.0 + r)), false),
-
-        // left side - bottom quarter
-        (Point::new(Mm(x0.0), Mm(y0.0 + r)), true),
-        (Point::new(Mm(x0.0), Mm(y0.0 + r - c)), true),
-        (Point::new(Mm(x0.0 + r - c), Mm(y0.0)), true),
-        (Point::new(Mm(x0.0 + r), Mm(y0.0)), false),
-    ];
-
-    let line = Line {
-        points: pts,
-        is_closed: true,
-        ..Default::default()
-    };
+/// Carry out the command line `args`, without the program name.
+fn run(args: impl Iterator<Item = String>) -> Result<(), CliError> {
+    match parse_args(args)? {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Render(args) => render(*args),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    match args.next().as_deref() {
+        Some("render") => {}
+        Some("-h") | Some("--help") => return Ok(Command::Help),
+        Some(other) => {
+            return Err(CliError::new(
+                EX_USAGE,
+                format!("unknown command {:?}", other),
+            ))
+        }
+        None => return Err(CliError::new(EX_USAGE, "missing command")),
+    }
+
+    let mut input = None;
+    let mut output = None;
+    let mut fonts = Vec::new();
+    let mut fallback_fonts = Vec::new();
+    let mut text_family = None;
+    let mut title_family = None;
+    let mut options = ReportOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .ok_or_else(|| CliError::new(EX_USAGE, format!("{} expects a value", name)))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-o" | "--output" => output = Some(PathBuf::from(value(&arg)?)),
+            "--font" => fonts.push(PathBuf::from(value(&arg)?)),
+            "--fallback-font" => fallback_fonts.push(PathBuf::from(value(&arg)?)),
+            "--text-family" => text_family = Some(value(&arg)?),
+            "--title-family" => title_family = Some(value(&arg)?),
+            "--full-fonts" => options.subset_fonts = false,
+            "--inter-octave" => options.inter_octave_lines = true,
+            "--lang" => {
+                options.lang = value(&arg)?
+                    .parse()
+                    .map_err(|e| CliError::new(EX_USAGE, e))?
+            }
+            "--paper" => {
+                options.paper = value(&arg)?
+                    .parse()
+                    .map_err(|e| CliError::new(EX_USAGE, e))?
+            }
+            "--legend" => {
+                options.legend = value(&arg)?
+                    .parse()
+                    .map_err(|e| CliError::new(EX_USAGE, e))?
+            }
+            "--colors" => {
+                options.colors = value(&arg)?
+                    .parse()
+                    .map_err(|e| CliError::new(EX_USAGE, e))?
+            }
+            "--notes-overflow" => {
+                options.notes_overflow = value(&arg)?
+                    .parse()
+                    .map_err(|e| CliError::new(EX_USAGE, e))?
+            }
+            _ if arg.starts_with('-') => {
+                return Err(CliError::new(EX_USAGE, format!("unknown option {:?}", arg)))
+            }
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => {
+                return Err(CliError::new(
+                    EX_USAGE,
+                    format!("unexpected argument {:?}", arg),
+                ))
+            }
+        }
+    }
+
+    Ok(Command::Render(Box::new(RenderArgs {
+        input: input.ok_or_else(|| CliError::new(EX_USAGE, "missing evaluation file"))?,
+        output: output.ok_or_else(|| CliError::new(EX_USAGE, "missing -o <output>"))?,
+        fonts,
+        fallback_fonts,
+        text_family,
+        title_family,
+        options,
+    })))
+}
+
+fn render(mut args: RenderArgs) -> Result<(), CliError> {
+    let eval = input::load(&args.input).map_err(|e| {
+        let code = match e {
+            input::Error::Io(_) => EX_NOINPUT,
+            _ => EX_DATAERR,
+        };
+        CliError::new(code, format!("{}: {}", args.input.display(), e))
+    })?;
+
+    // The first --font names the family the report is set in, unless
+    // chosen explicitly; fallback families back up both text and titles
+    let registry = &mut args.options.fonts;
+    let mut main_family = None;
+    for path in &args.fonts {
+        let family = add_font(registry, path)?;
+        main_family.get_or_insert(family);
+    }
+    let mut fallback_families = Vec::new();
+    for path in &args.fallback_fonts {
+        let family = add_font(registry, path)?;
+        if !fallback_families.contains(&family) {
+            fallback_families.push(family);
+        }
+    }
+    let text_family = args
+        .text_family
+        .or(main_family)
+        .unwrap_or_else(|| args.options.text_font.families[0].clone());
+    let title_family = args.title_family.unwrap_or_else(|| text_family.clone());
+    for (selection, family) in [
+        (&mut args.options.text_font, text_family),
+        (&mut args.options.title_font, title_family),
+    ] {
+        if !args.options.fonts.has_family(&family) {
+            return Err(CliError::new(
+                EX_USAGE,
+                format!(
+                    "unknown font family {:?}; add its files with --font",
+                    family
+                ),
+            ));
+        }
+        selection.families = std::iter::once(family)
+            .chain(fallback_families.iter().cloned())
+            .collect();
+    }
 
-    layer.add_line(line);
+    // Lay out fully in memory so a failure never leaves a truncated file
+    let svg = args
+        .output
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+    let rendered = if svg {
+        report::render_svg(&eval, &args.options).map(String::into_bytes)
+    } else {
+        report::render(&eval, &args.options)
+            .and_then(|doc| doc.save_to_bytes().map_err(report::Error::from))
+    };
+    let bytes = rendered.map_err(render_error)?;
+
+    std::fs::write(&args.output, bytes).map_err(|e| {
+        CliError::new(
+            EX_CANTCREAT,
+            format!("cannot write {}: {}", args.output.display(), e),
+        )
+    })
 }
 
-/* helper: draw a rectangle with rounded corners */
-fn draw_rounded_rect(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    w: Mm,
-    h: Mm,
-    r: Mm,
-) {
-    use printpdf::{Line, Point};
-
-    const C: f32 = 0.55191505; // bezier approximation constant
-
-    let (x0, y0) = origin;
-    let (w, h, r) = (w.0, h.0, r.0);
-    let c = r * C;
-
-    let pts = vec![
-        // start at bottom left after corner
-        (Point::new(Mm(x0.0 + r), Mm(y0.0)), false),
-        // bottom edge
-        (Point::new(Mm(x0.0 + w - r), Mm(y0.0)), false),
-
-        // bottom-right corner
-        (Point::new(Mm(x0.0 + w - r), Mm(y0.0)), true),
-        (Point::new(Mm(x0.0 + w - r + c), Mm(y0.0)), true),
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + r - c)), true),
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + r)), false),
-
-        // right edge
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + h - r)), false),
-
-        // top-right corner
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + h - r)), true),
-        (Point::new(Mm(x0.0 + w), Mm(y0.0 + h - r + c)), true),
-        (Point::new(Mm(x0.0 + w - r + c), Mm(y0.0 + h)), true),
-        (Point::new(Mm(x0.0 + w - r), Mm(y0.0 + h)), false),
-
-        // top edge
-        (Point::new(Mm(x0.0 + r), Mm(y0.0 + h)), false),
-
-        // top-left corner
-        (Point::new(Mm(x0.0 + r), Mm(y0.0 + h)), true),
-        (Point::new(Mm(x0.0 + r - c), Mm(y0.0 + h)), true),
-        (Point::new(Mm(x0.0), Mm(y0.0 + h - r + c)), true),
-        (Point::new(Mm(x0.0), Mm(y0.0 + h - r)), false),
-
-        // left edge
-        (Point::new(Mm(x0.0), Mm(y0.0 + r)), false),
-
-        // bottom-left corner
-        (Point::new(Mm(x0.0), Mm(y0.0 + r)), true),
-        (Point::new(Mm(x0.0), Mm(y0.0 + r - c)), true),
-        (Point::new(Mm(x0.0 + r - c), Mm(y0.0)), true),
-        (Point::new(Mm(x0.0 + r), Mm(y0.0)), false),
-    ];
-
-    let line = Line {
-        points: pts,
-        is_closed: true,
-        ..Default::default()
+fn render_error(e: report::Error) -> CliError {
+    let code = match e {
+        // text the font has no glyphs for is a problem with the input
+        report::Error::Text(_) | report::Error::Font(_) => EX_DATAERR,
+        report::Error::Pdf(_) => EX_SOFTWARE,
     };
+    CliError::new(code, format!("cannot render report: {}", e))
+}
+
+/// Register the font at `path`. Returns its family.
+fn add_font(registry: &mut FontRegistry, path: &Path) -> Result<String, CliError> {
+    let id = registry.add_font_file(path).map_err(|e| match e {
+        fonts::Error::Io(e) => CliError::new(
+            EX_NOINPUT,
+            format!("cannot read font {}: {}", path.display(), e),
+        ),
+        e => CliError::new(EX_DATAERR, format!("{}: {}", path.display(), e)),
+    })?;
+    Ok(registry.faces()[id].family.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    /// The exit code `line` fails with.
+    fn code(line: &str) -> u8 {
+        match run(args(line)) {
+            Ok(()) => panic!("{:?} succeeded", line),
+            Err(e) => e.code,
+        }
+    }
+
+    fn example() -> String {
+        format!("{}/examples/jane_doe.json", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    /// A file in the temporary directory, removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &str) -> Self {
+            let name = format!("svg_report-{}-{}", std::process::id(), name);
+            let path = std::env::temp_dir().join(name);
+            std::fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+    }
 
-    layer.add_line(line);
+    impl fmt::Display for TempFile {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.0.display())
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn parses_render_options() {
+        let Ok(Command::Render(render)) = parse_args(args(
+            "render in.json --lang en -o out.svg --paper letter --full-fonts --font a.ttf --font b.ttf",
+        )) else {
+            panic!("expected a render command");
+        };
+        assert_eq!(render.input, PathBuf::from("in.json"));
+        assert_eq!(render.output, PathBuf::from("out.svg"));
+        assert_eq!(
+            render.fonts,
+            [PathBuf::from("a.ttf"), PathBuf::from("b.ttf")]
+        );
+        assert!(!render.options.subset_fonts);
+        assert!(matches!(
+            parse_args(args("render --help")),
+            Ok(Command::Help)
+        ));
+        assert!(matches!(parse_args(args("-h")), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn bad_command_lines_are_usage_errors() {
+        for line in [
+            "",
+            "draw in.json -o out.pdf",
+            "render in.json -o out.pdf --colour red",
+            "render in.json -o",
+            "render in.json -o out.pdf --lang",
+            "render in.json -o out.pdf --lang de",
+            "render in.json",
+            "render -o out.pdf",
+            "render a.json b.json -o out.pdf",
+        ] {
+            assert_eq!(
+                parse_args(args(line)).err().map(|e| e.code),
+                Some(EX_USAGE),
+                "{:?}",
+                line
+            );
+        }
+        let Err(e) = parse_args(args("render in.json --font")) else {
+            panic!("expected an error");
+        };
+        assert_eq!(e.message, "--font expects a value");
+    }
+
+    #[test]
+    fn unreadable_files_are_no_input() {
+        assert_eq!(code("render /nonexistent/in.json -o out.pdf"), EX_NOINPUT);
+        let line = format!("render {} -o out.pdf --font /nonexistent/a.ttf", example());
+        assert_eq!(code(&line), EX_NOINPUT);
+    }
+
+    #[test]
+    fn invalid_files_are_data_errors() {
+        let input = TempFile::new("invalid.json", "{ \"patient\": 1 }");
+        assert_eq!(code(&format!("render {} -o out.pdf", input)), EX_DATAERR);
+        let font = TempFile::new("font.ttf", "not a font");
+        let line = format!("render {} -o out.pdf --font {}", example(), font);
+        assert_eq!(code(&line), EX_DATAERR);
+    }
+
+    #[test]
+    fn pdf_writer_failures_are_software_errors() {
+        let e = printpdf::Error::Pdf(printpdf::PdfError::FontFaceError);
+        assert_eq!(render_error(report::Error::Pdf(e)).code, EX_SOFTWARE);
+    }
+
+    #[test]
+    fn unwritable_output_cannot_be_created() {
+        let line = format!("render {} -o /nonexistent/out.svg", example());
+        assert_eq!(code(&line), EX_CANTCREAT);
+    }
 }
@@ -0,0 +1,868 @@
+//! Evaluation data model rendered by the report.
+//!
+//! Everything printed on the audiogram page comes from an [`Evaluation`];
+//! the layout code never carries patient data of its own.
+
+use std::fmt;
+
+use serde::Deserialize;
+
+/// Octave frequencies (Hz) on the tonal audiogram grid.
+pub const OCTAVE_FREQUENCIES: [u32; 7] = [125, 250, 500, 1000, 2000, 4000, 8000];
+
+/// Half-octave frequencies (Hz) tested between the octaves.
+pub const INTER_OCTAVE_FREQUENCIES: [u32; 4] = [750, 1500, 3000, 6000];
+
+/// Extended high frequencies (Hz), shown only when tested.
+pub const EXTENDED_HIGH_FREQUENCIES: [u32; 3] = [10000, 12500, 16000];
+
+/// Whether thresholds can be measured and plotted at `frequency`.
+pub fn is_test_frequency(frequency: u32) -> bool {
+    OCTAVE_FREQUENCIES.contains(&frequency)
+        || INTER_OCTAVE_FREQUENCIES.contains(&frequency)
+        || EXTENDED_HIGH_FREQUENCIES.contains(&frequency)
+}
+
+/// Lowest level (dB HL) on the tonal audiogram grid.
+pub const MIN_LEVEL: i32 = -10;
+
+/// Highest level (dB HL) on the tonal audiogram grid.
+pub const MAX_LEVEL: i32 = 120;
+
+/// Calendar date, as printed on the report (`YYYY-MM-DD`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        Date { year, month, day }
+    }
+
+    /// Parse an ISO `YYYY-MM-DD` date.
+    pub fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || !(1..=12).contains(&month) {
+            return None;
+        }
+        if !(1..=days_in_month(year, month)).contains(&day) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Age in completed years on `on`.
+    pub fn age_at(&self, on: Date) -> i32 {
+        let mut age = on.year - self.year;
+        if (on.month, on.day) < (self.month, self.day) {
+            age -= 1;
+        }
+        age
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Ear {
+    Right,
+    Left,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Patient {
+    pub first_name: String,
+    pub last_name: String,
+    pub date_of_birth: Date,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clinician {
+    pub name: String,
+    /// Degree printed after the name, e.g. `MPA`.
+    pub credentials: String,
+    /// Professional order licence number.
+    pub licence: String,
+}
+
+/// Reliability of the patient's responses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Validity {
+    Good,
+    Fair,
+    Poor,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stimulus {
+    PureTone,
+    Warble,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Conduction {
+    Air,
+    Bone,
+}
+
+/// One measured threshold on the tonal audiogram.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Threshold {
+    /// Test frequency in Hz.
+    pub frequency: u32,
+    /// Level in dB HL.
+    pub level: i32,
+    pub masked: bool,
+    /// No response at the audiometer's maximum output; `level` is that
+    /// maximum.
+    pub no_response: bool,
+}
+
+/// Air and bone conduction thresholds for one ear.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EarThresholds {
+    pub air: Vec<Threshold>,
+    pub bone: Vec<Threshold>,
+}
+
+impl EarThresholds {
+    pub fn air_at(&self, frequency: u32) -> Option<&Threshold> {
+        self.air.iter().find(|t| t.frequency == frequency)
+    }
+
+    /// Pure-tone average over 500, 1000 and 2000 Hz (MSP3). Like the other
+    /// averages, `None` unless every frequency was tested; no-response
+    /// thresholds count at their level, the audiometer's maximum.
+    pub fn pta3(&self) -> Option<f32> {
+        self.air_average(&[500, 1000, 2000])
+    }
+
+    /// Pure-tone average over 500, 1000, 2000 and 4000 Hz (MSP4).
+    pub fn pta4(&self) -> Option<f32> {
+        self.air_average(&[500, 1000, 2000, 4000])
+    }
+
+    /// Fletcher average: the two best air thresholds among 500, 1000 and
+    /// 2000 Hz (FLCH).
+    pub fn fletcher(&self) -> Option<f32> {
+        let mut levels = [500, 1000, 2000]
+            .iter()
+            .map(|f| self.air_at(*f).map(|t| t.level))
+            .collect::<Option<Vec<_>>>()?;
+        levels.sort_unstable();
+        Some((levels[0] + levels[1]) as f32 / 2.0)
+    }
+
+    fn air_average(&self, frequencies: &[u32]) -> Option<f32> {
+        let sum = frequencies
+            .iter()
+            .map(|f| self.air_at(*f).map(|t| t.level))
+            .sum::<Option<i32>>()?;
+        Some(sum as f32 / frequencies.len() as f32)
+    }
+}
+
+/// Tonal audiogram for both ears.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Audiogram {
+    pub right: EarThresholds,
+    pub left: EarThresholds,
+}
+
+impl Audiogram {
+    pub fn ear(&self, ear: Ear) -> &EarThresholds {
+        match ear {
+            Ear::Right => &self.right,
+            Ear::Left => &self.left,
+        }
+    }
+
+    /// Whether either ear was tested above 8000 Hz.
+    pub fn has_extended_high_frequencies(&self) -> bool {
+        [&self.right, &self.left]
+            .iter()
+            .flat_map(|ear| ear.air.iter().chain(&ear.bone))
+            .any(|t| EXTENDED_HIGH_FREQUENCIES.contains(&t.frequency))
+    }
+}
+
+/// How speech material was presented.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modality {
+    /// Monitored live voice.
+    LiveVoice,
+    Recorded,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WordRecognition {
+    /// Percentage of words correctly repeated.
+    pub score: f32,
+    /// Presentation level in dB HL.
+    pub level: i32,
+    /// Word list used, e.g. `2A`.
+    pub list: Option<String>,
+    /// Masking noise in the opposite ear, in dB EM.
+    pub masking: Option<i32>,
+    pub modality: Option<Modality>,
+}
+
+/// A word-recognition score at one presentation level, as plotted on the
+/// performance-intensity (PI-PB) function.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PiPoint {
+    /// Presentation level in dB HL.
+    pub level: i32,
+    /// Percentage of words correctly repeated.
+    pub score: f32,
+}
+
+/// Speech-in-noise test.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoiseTest {
+    QuickSin,
+    Hint,
+    /// A French-language sentences-in-noise test.
+    FrenchSin,
+}
+
+impl NoiseTest {
+    /// Name printed on the report, the same in every language.
+    pub fn name(self) -> &'static str {
+        match self {
+            NoiseTest::QuickSin => "QuickSIN",
+            NoiseTest::Hint => "HINT",
+            NoiseTest::FrenchSin => "SIN-Fr",
+        }
+    }
+}
+
+/// Degree of SNR loss, after the QuickSIN manual.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SnrLossCategory {
+    Normal,
+    Mild,
+    Moderate,
+    Severe,
+}
+
+impl SnrLossCategory {
+    /// Category of an SNR loss in dB: up to 3 is normal, 7 mild, 15
+    /// moderate, severe beyond.
+    pub fn from_snr_loss(snr_loss: f32) -> Self {
+        if snr_loss <= 3.0 {
+            SnrLossCategory::Normal
+        } else if snr_loss <= 7.0 {
+            SnrLossCategory::Mild
+        } else if snr_loss <= 15.0 {
+            SnrLossCategory::Moderate
+        } else {
+            SnrLossCategory::Severe
+        }
+    }
+}
+
+/// Speech-in-noise results. SNRs are in dB; values the test does not
+/// report are `None`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpeechInNoise {
+    pub test: NoiseTest,
+    /// Signal-to-noise ratio at which half the material is repeated.
+    pub snr50: Option<f32>,
+    /// SNR-50 above that of normal listeners.
+    pub snr_loss: Option<f32>,
+}
+
+impl SpeechInNoise {
+    pub fn category(&self) -> Option<SnrLossCategory> {
+        self.snr_loss.map(SnrLossCategory::from_snr_loss)
+    }
+}
+
+/// Speech audiometry results for one ear. Levels are in dB HL; tests not
+/// done are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EarSpeech {
+    /// Speech reception threshold.
+    pub srt: Option<i32>,
+    /// Speech detection threshold.
+    pub sdt: Option<i32>,
+    /// Most comfortable level.
+    pub mcl: Option<i32>,
+    /// Uncomfortable level.
+    pub ucl: Option<i32>,
+    pub word_recognition: Option<WordRecognition>,
+    /// Word-recognition scores at further levels, sorted by level.
+    pub performance_intensity: Vec<PiPoint>,
+    pub in_noise: Option<SpeechInNoise>,
+}
+
+impl EarSpeech {
+    /// The performance-intensity function: `performance_intensity` and the
+    /// word-recognition score, sorted by level.
+    pub fn pi_function(&self) -> Vec<PiPoint> {
+        let mut points = self.performance_intensity.clone();
+        if let Some(wr) = &self.word_recognition {
+            if !points.iter().any(|p| p.level == wr.level) {
+                points.push(PiPoint {
+                    level: wr.level,
+                    score: wr.score,
+                });
+            }
+        }
+        points.sort_by_key(|p| p.level);
+        points
+    }
+
+    /// Rollover index (PBmax - PBmin) / PBmax, where PBmin is the poorest
+    /// score at a level above that of the best score, PBmax. `None` until
+    /// a level above PBmax's was tested.
+    pub fn rollover_index(&self) -> Option<f32> {
+        let points = self.pi_function();
+        let (i, max) = points
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.score.total_cmp(&b.score).then(b.level.cmp(&a.level)))?;
+        let min = points[i + 1..]
+            .iter()
+            .map(|p| p.score)
+            .min_by(f32::total_cmp)?;
+        if max.score <= 0.0 {
+            return None;
+        }
+        Some((max.score - min) / max.score)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Speech {
+    pub right: EarSpeech,
+    pub left: EarSpeech,
+}
+
+impl Speech {
+    pub fn ear(&self, ear: Ear) -> &EarSpeech {
+        match ear {
+            Ear::Right => &self.right,
+            Ear::Left => &self.left,
+        }
+    }
+}
+
+/// One point of a tympanogram sweep.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TympanogramSample {
+    /// Ear canal pressure in daPa.
+    pub pressure: f32,
+    /// Compliance in ml.
+    pub compliance: f32,
+}
+
+/// A tympanogram: the summary values read off it and, when the
+/// tympanometer exports them, the samples of the sweep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tympanogram {
+    /// Pressure at peak compliance in daPa.
+    pub peak_pressure: f32,
+    /// Peak compliance in ml.
+    pub peak_compliance: f32,
+    /// Ear canal volume in ml.
+    pub ear_canal_volume: f32,
+    /// Width of the peak at half its height (tympanometric width), in daPa.
+    pub gradient: Option<f32>,
+    /// Sorted by pressure.
+    pub samples: Vec<TympanogramSample>,
+}
+
+impl Tympanogram {
+    /// Peak pressure, static admittance and width, measured on the samples
+    /// when there are any and taken from the summary values otherwise.
+    /// `None` when the sampled curve peaks at an end of the sweep.
+    pub fn measures(&self) -> Option<TympanogramMeasures> {
+        let samples = &self.samples;
+        if samples.is_empty() {
+            return Some(TympanogramMeasures {
+                peak_pressure: self.peak_pressure,
+                static_admittance: self.peak_compliance,
+                width: self.gradient,
+            });
+        }
+
+        let (peak, top) = samples
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.compliance.total_cmp(&b.compliance))?;
+        if peak == 0 || peak == samples.len() - 1 {
+            return None;
+        }
+        let baseline = self.baseline();
+        let static_admittance = top.compliance - baseline;
+        let half = baseline + static_admittance / 2.0;
+
+        // a peak no higher than the tail has no half height to measure at
+        let width = if static_admittance > 0.0 {
+            let left = half_crossing(samples, peak, half, (0..peak).rev());
+            let right = half_crossing(samples, peak, half, peak + 1..samples.len());
+            left.zip(right).map(|(left, right)| right - left)
+        } else {
+            None
+        };
+
+        Some(TympanogramMeasures {
+            peak_pressure: top.pressure,
+            static_admittance,
+            width,
+        })
+    }
+
+    /// Compliance in ml that peaks are measured from: the positive tail,
+    /// as tympanometers compensate it, or zero without samples.
+    pub fn baseline(&self) -> f32 {
+        self.samples.last().map_or(0.0, |s| s.compliance)
+    }
+
+    /// Jerger type of the tympanogram against `norms`.
+    pub fn jerger_type(&self, norms: &TympanogramNorms) -> JergerType {
+        let Some(m) = self.measures() else {
+            return JergerType::B;
+        };
+        let (min_admittance, max_admittance) = norms.admittance;
+        if m.static_admittance < norms.min_peak {
+            JergerType::B
+        } else if m.peak_pressure < norms.min_pressure {
+            JergerType::C
+        } else if m.static_admittance < min_admittance {
+            JergerType::As
+        } else if m.static_admittance > max_admittance {
+            JergerType::Ad
+        } else {
+            JergerType::A
+        }
+    }
+}
+
+/// Pressure where the curve falls to `half`, walking away from the sample
+/// at `peak` along `indices`.
+fn half_crossing(
+    samples: &[TympanogramSample],
+    peak: usize,
+    half: f32,
+    indices: impl Iterator<Item = usize>,
+) -> Option<f32> {
+    let mut inner = samples[peak];
+    for i in indices {
+        let outer = samples[i];
+        if outer.compliance <= half {
+            if inner.compliance == outer.compliance {
+                return None;
+            }
+            let t = (inner.compliance - half) / (inner.compliance - outer.compliance);
+            return Some(inner.pressure + t * (outer.pressure - inner.pressure));
+        }
+        inner = outer;
+    }
+    None
+}
+
+/// Values measured on a tympanogram.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TympanogramMeasures {
+    /// Pressure at the peak in daPa.
+    pub peak_pressure: f32,
+    /// Height of the peak above the positive tail, in ml.
+    pub static_admittance: f32,
+    /// Tympanometric width in daPa; `None` when the curve does not fall to
+    /// half the peak height on both sides within the sweep.
+    pub width: Option<f32>,
+}
+
+/// Jerger tympanogram types.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JergerType {
+    /// Normal peak pressure and admittance.
+    A,
+    /// Normal peak pressure, shallow peak.
+    As,
+    /// Normal peak pressure, deep peak.
+    Ad,
+    /// No peak.
+    B,
+    /// Peak at negative pressure.
+    C,
+}
+
+impl JergerType {
+    pub fn name(self) -> &'static str {
+        match self {
+            JergerType::A => "A",
+            JergerType::As => "As",
+            JergerType::Ad => "Ad",
+            JergerType::B => "B",
+            JergerType::C => "C",
+        }
+    }
+}
+
+/// Normative ranges tympanograms are classified against. The default is
+/// for adults.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TympanogramNorms {
+    /// Lowest normal peak pressure in daPa; peaks below it are type C.
+    /// Jerger types have no upper bound: positive peaks are classified on
+    /// their admittance alone.
+    pub min_pressure: f32,
+    /// Normal static admittance in ml; below it is type As, above it Ad.
+    pub admittance: (f32, f32),
+    /// Static admittance in ml below which there is no peak, type B.
+    pub min_peak: f32,
+}
+
+impl Default for TympanogramNorms {
+    fn default() -> Self {
+        TympanogramNorms {
+            min_pressure: -100.0,
+            admittance: (0.3, 1.5),
+            min_peak: 0.1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReflexRoute {
+    Ipsilateral,
+    Contralateral,
+}
+
+/// Acoustic (stapedial) reflex threshold. `level` is `None` when no
+/// reflex was elicited.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Reflex {
+    pub frequency: u32,
+    pub route: ReflexRoute,
+    pub level: Option<i32>,
+}
+
+/// Immittance results for one ear.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EarImmittance {
+    pub tympanogram: Option<Tympanogram>,
+    pub reflexes: Vec<Reflex>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Immittance {
+    pub right: EarImmittance,
+    pub left: EarImmittance,
+}
+
+impl Immittance {
+    pub fn ear(&self, ear: Ear) -> &EarImmittance {
+        match ear {
+            Ear::Right => &self.right,
+            Ear::Left => &self.left,
+        }
+    }
+}
+
+/// A complete audiological evaluation: everything the report prints.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluation {
+    pub patient: Patient,
+    pub clinician: Clinician,
+    pub date: Date,
+    pub place: String,
+    pub validity: Validity,
+    pub stimulus: Stimulus,
+    pub method: String,
+    pub audiogram: Audiogram,
+    pub speech: Speech,
+    pub immittance: Immittance,
+    pub notes: String,
+}
+
+impl Evaluation {
+    pub fn patient_age(&self) -> i32 {
+        self.patient.date_of_birth.age_at(self.date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn air(levels: &[(u32, i32, bool)]) -> EarThresholds {
+        EarThresholds {
+            air: levels
+                .iter()
+                .map(|&(frequency, level, no_response)| Threshold {
+                    frequency,
+                    level,
+                    masked: false,
+                    no_response,
+                })
+                .collect(),
+            bone: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn dates_parse_and_print_as_iso() {
+        let date = Date::parse("2025-06-27").unwrap();
+        assert_eq!(date, Date::new(2025, 6, 27));
+        assert_eq!(date.to_string(), "2025-06-27");
+        assert_eq!(Date::parse("2025-6-7"), Some(Date::new(2025, 6, 7)));
+        for bad in [
+            "",
+            "2025-06",
+            "2025-06-27-01",
+            "2025-13-01",
+            "2025-00-10",
+            "2025-06-x",
+        ] {
+            assert_eq!(Date::parse(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn dates_exist_in_their_month() {
+        assert_eq!(Date::parse("2025-01-31"), Some(Date::new(2025, 1, 31)));
+        assert_eq!(Date::parse("2025-02-31"), None);
+        assert_eq!(Date::parse("2025-04-31"), None);
+        assert_eq!(Date::parse("2025-04-30"), Some(Date::new(2025, 4, 30)));
+        assert_eq!(Date::parse("2025-02-29"), None);
+        assert_eq!(Date::parse("2024-02-29"), Some(Date::new(2024, 2, 29)));
+        assert_eq!(Date::parse("1900-02-29"), None);
+        assert_eq!(Date::parse("2000-02-29"), Some(Date::new(2000, 2, 29)));
+        assert_eq!(Date::parse("2025-06-00"), None);
+    }
+
+    #[test]
+    fn age_changes_on_the_birthday() {
+        let birth = Date::new(1954, 6, 10);
+        assert_eq!(birth.age_at(Date::new(2025, 6, 9)), 70);
+        assert_eq!(birth.age_at(Date::new(2025, 6, 10)), 71);
+        assert_eq!(birth.age_at(Date::new(2025, 12, 31)), 71);
+        assert_eq!(birth.age_at(birth), 0);
+    }
+
+    #[test]
+    fn leap_day_birthdays_come_on_march_1st() {
+        let birth = Date::new(2000, 2, 29);
+        assert_eq!(birth.age_at(Date::new(2001, 2, 28)), 0);
+        assert_eq!(birth.age_at(Date::new(2001, 3, 1)), 1);
+        assert_eq!(birth.age_at(Date::new(2004, 2, 28)), 3);
+        assert_eq!(birth.age_at(Date::new(2004, 2, 29)), 4);
+    }
+
+    #[test]
+    fn pure_tone_averages() {
+        let ear = air(&[
+            (500, 30, false),
+            (1000, 45, false),
+            (2000, 50, false),
+            (4000, 70, false),
+        ]);
+        assert_eq!(ear.pta3(), Some(125.0 / 3.0));
+        assert_eq!(ear.pta4(), Some(48.75));
+        // the two best of 30, 45 and 50
+        assert_eq!(ear.fletcher(), Some(37.5));
+    }
+
+    #[test]
+    fn averages_need_every_frequency() {
+        let ear = air(&[(500, 30, false), (1000, 45, false), (4000, 70, false)]);
+        assert_eq!(ear.pta3(), None);
+        assert_eq!(ear.pta4(), None);
+        assert_eq!(ear.fletcher(), None);
+        assert_eq!(EarThresholds::default().pta3(), None);
+    }
+
+    #[test]
+    fn no_response_counts_at_the_maximum_output() {
+        let ear = air(&[
+            (500, 40, false),
+            (1000, 60, false),
+            (2000, 110, true),
+            (4000, 110, true),
+        ]);
+        assert_eq!(ear.pta3(), Some(70.0));
+        assert_eq!(ear.pta4(), Some(80.0));
+        assert_eq!(ear.fletcher(), Some(50.0));
+    }
+
+    /// Tympanogram sampled every 25 daPa over -400..200 from a bell of
+    /// `height` ml and half-height width `width` daPa at `peak`.
+    fn tympanogram(peak: f32, height: f32, width: f32) -> Tympanogram {
+        let samples = (-16..=8)
+            .map(|i| {
+                let pressure = i as f32 * 25.0;
+                let x = (pressure - peak) / (width / 2.0);
+                TympanogramSample {
+                    pressure,
+                    compliance: height / (1.0 + x * x),
+                }
+            })
+            .collect();
+        Tympanogram {
+            peak_pressure: 0.0,
+            peak_compliance: 0.0,
+            ear_canal_volume: 1.0,
+            gradient: None,
+            samples,
+        }
+    }
+
+    #[test]
+    fn measures_come_from_the_samples() {
+        let m = tympanogram(-25.0, 0.8, 100.0).measures().unwrap();
+        assert_eq!(m.peak_pressure, -25.0);
+        // measured from the tail at +200 daPa, still 0.04 ml up
+        assert!((m.static_admittance - 0.76).abs() < 0.01, "{:?}", m);
+        // the samples are coarser than the curve: about 100 daPa wide
+        let width = m.width.unwrap();
+        assert!((95.0..=110.0).contains(&width), "{:?}", m);
+
+        // a curve still rising at -400 daPa has no peak to measure
+        assert_eq!(tympanogram(-500.0, 0.8, 100.0).measures(), None);
+    }
+
+    #[test]
+    fn flat_tympanograms_have_no_width() {
+        let mut t = tympanogram(0.0, 0.8, 100.0);
+        for sample in &mut t.samples {
+            sample.compliance = 0.3;
+        }
+        assert_eq!(t.measures(), None);
+        assert_eq!(t.jerger_type(&TympanogramNorms::default()), JergerType::B);
+
+        // walking off a flat peak never divides by its zero height
+        let samples = [(-100.0, 0.3), (0.0, 0.3), (100.0, 0.3)].map(|(pressure, compliance)| {
+            TympanogramSample {
+                pressure,
+                compliance,
+            }
+        });
+        assert_eq!(half_crossing(&samples, 1, 0.3, 2..3), None);
+        assert_eq!(half_crossing(&samples, 1, 0.3, (0..1).rev()), None);
+    }
+
+    #[test]
+    fn jerger_types() {
+        let norms = TympanogramNorms::default();
+        let jerger = |peak, height| tympanogram(peak, height, 100.0).jerger_type(&norms);
+        assert_eq!(jerger(0.0, 0.8), JergerType::A);
+        assert_eq!(jerger(0.0, 0.2), JergerType::As);
+        assert_eq!(jerger(25.0, 2.0), JergerType::Ad);
+        assert_eq!(jerger(0.0, 0.05), JergerType::B);
+        assert_eq!(jerger(-500.0, 0.8), JergerType::B);
+        assert_eq!(jerger(-200.0, 0.8), JergerType::C);
+
+        // ranges are configurable, e.g. for children
+        let child = TympanogramNorms {
+            min_pressure: -150.0,
+            admittance: (0.2, 0.9),
+            ..norms
+        };
+        let t = tympanogram(-125.0, 0.25, 100.0);
+        assert_eq!(t.jerger_type(&norms), JergerType::C);
+        assert_eq!(t.jerger_type(&child), JergerType::A);
+    }
+
+    #[test]
+    fn jerger_type_boundaries() {
+        let norms = TympanogramNorms::default();
+        let jerger = |pressure, admittance| {
+            let mut t = tympanogram(0.0, 0.8, 100.0);
+            t.samples.clear();
+            t.peak_pressure = pressure;
+            t.peak_compliance = admittance;
+            t.jerger_type(&norms)
+        };
+        // the lowest normal pressure is still normal
+        assert_eq!(jerger(-100.0, 0.7), JergerType::A);
+        assert_eq!(jerger(-101.0, 0.7), JergerType::C);
+        assert_eq!(jerger(-101.0, 0.2), JergerType::C);
+        // positive peaks, however high, are not a type of their own
+        assert_eq!(jerger(50.0, 0.7), JergerType::A);
+        assert_eq!(jerger(51.0, 0.7), JergerType::A);
+        assert_eq!(jerger(200.0, 0.2), JergerType::As);
+        assert_eq!(jerger(200.0, 1.6), JergerType::Ad);
+        // admittance bounds are normal too
+        assert_eq!(jerger(0.0, 0.3), JergerType::A);
+        assert_eq!(jerger(0.0, 1.5), JergerType::A);
+        assert_eq!(jerger(0.0, 0.29), JergerType::As);
+        assert_eq!(jerger(0.0, 1.51), JergerType::Ad);
+        assert_eq!(jerger(0.0, 0.1), JergerType::As);
+        assert_eq!(jerger(0.0, 0.09), JergerType::B);
+    }
+
+    #[test]
+    fn jerger_type_of_summary_values() {
+        let mut t = tympanogram(0.0, 0.8, 100.0);
+        t.samples.clear();
+        t.peak_pressure = -20.0;
+        t.peak_compliance = 0.7;
+        assert_eq!(t.jerger_type(&TympanogramNorms::default()), JergerType::A);
+        t.peak_compliance = 0.0;
+        assert_eq!(t.jerger_type(&TympanogramNorms::default()), JergerType::B);
+    }
+
+    fn speech(scores: &[(i32, f32)]) -> EarSpeech {
+        EarSpeech {
+            performance_intensity: scores
+                .iter()
+                .map(|&(level, score)| PiPoint { level, score })
+                .collect(),
+            ..EarSpeech::default()
+        }
+    }
+
+    #[test]
+    fn rollover_index_uses_the_poorest_score_above_the_best() {
+        let ear = speech(&[(50, 60.0), (60, 80.0), (70, 80.0), (80, 72.0), (90, 60.0)]);
+        assert_eq!(ear.rollover_index(), Some(0.25));
+        // PBmax at the top level: nothing rolled over yet
+        assert_eq!(speech(&[(50, 60.0), (70, 90.0)]).rollover_index(), None);
+        assert_eq!(speech(&[]).rollover_index(), None);
+    }
+
+    #[test]
+    fn pi_function_includes_word_recognition() {
+        let mut ear = speech(&[(60, 70.0), (90, 76.0)]);
+        ear.word_recognition = Some(WordRecognition {
+            score: 88.0,
+            level: 75,
+            list: None,
+            masking: None,
+            modality: None,
+        });
+        let levels: Vec<i32> = ear.pi_function().iter().map(|p| p.level).collect();
+        assert_eq!(levels, [60, 75, 90]);
+        assert_eq!(ear.rollover_index(), Some(12.0 / 88.0));
+    }
+}
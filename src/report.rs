@@ -0,0 +1,2654 @@
+use printpdf::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::canvas::{Canvas, Paint, Path, PdfCanvas, RecordingCanvas, SvgCanvas, SvgFont};
+use crate::fonts::{self, FontData, FontRegistry, FontSelection, Style, Weight};
+use crate::labels::{Labels, Lang};
+use crate::model::{
+    Audiogram, Conduction, Ear, EarImmittance, EarSpeech, EarThresholds, Evaluation, Immittance,
+    Modality, ReflexRoute, SnrLossCategory, Speech, Stimulus, Threshold, Tympanogram,
+    TympanogramNorms, Validity, EXTENDED_HIGH_FREQUENCIES, INTER_OCTAVE_FREQUENCIES, MAX_LEVEL,
+    MIN_LEVEL, OCTAVE_FREQUENCIES,
+};
+use crate::shapes::{draw_symbol, pt_to_mm, BoundingBox, Symbol, SymbolColor, SymbolStyle};
+use crate::subset;
+use crate::text::{
+    self, draw_fitted_text, draw_paragraph, draw_text_in_box, HAlign, Overflow, ParagraphStyle,
+    TextMeasurer, VAlign,
+};
+
+// The page is laid out on A4 in millimeters, then fitted to the paper size
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 10.0;
+
+// Font sizes
+const FONT_SIZE_LARGE_TITLE: f32 = 18.;
+const FONT_SIZE_TITLE: f32 = 11.;
+const FONT_SIZE_NORMAL: f32 = 9.;
+const FONT_SIZE_SMALL: f32 = 7.;
+const FONT_SIZE_TINY: f32 = 5.;
+const FONT_SIZE_MIN_NOTES: f32 = 6.;
+
+// Size of the threshold symbols plotted on the audiogram, in points
+const THRESHOLD_SYMBOL_SIZE: f32 = 8.;
+const NO_RESPONSE_ARROW_SIZE: f32 = 6.;
+const LEGEND_SYMBOL_SIZE: f32 = 6.;
+
+// Tympanogram axes: pressure in daPa, compliance in ml
+const TYMPANOGRAM_PRESSURE: (f32, f32) = (-400.0, 200.0);
+const TYMPANOGRAM_MAX_COMPLIANCE: f32 = 2.5;
+
+// Size of the ear symbols on the PI-PB chart, in points
+const PI_SYMBOL_SIZE: f32 = 4.;
+
+/// Why a report could not be laid out.
+#[derive(Debug)]
+pub enum Error {
+    /// The PDF writer rejected the document or its font.
+    Pdf(printpdf::Error),
+    /// The report font cannot set some of the text, or cannot be read.
+    Text(text::Error),
+    /// A font family the report is set in is not registered.
+    Font(fonts::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Pdf(e) => write!(f, "{}", e),
+            Error::Text(e) => write!(f, "{}", e),
+            Error::Font(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Pdf(e) => Some(e),
+            Error::Text(e) => Some(e),
+            Error::Font(e) => Some(e),
+        }
+    }
+}
+
+impl From<printpdf::Error> for Error {
+    fn from(e: printpdf::Error) -> Self {
+        Error::Pdf(e)
+    }
+}
+
+impl From<text::Error> for Error {
+    fn from(e: text::Error) -> Self {
+        Error::Text(e)
+    }
+}
+
+impl From<fonts::Error> for Error {
+    fn from(e: fonts::Error) -> Self {
+        Error::Font(e)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Paper {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl Paper {
+    /// Width and height in millimeters.
+    pub fn size(self) -> (f32, f32) {
+        match self {
+            Paper::A4 => (210.0, 297.0),
+            Paper::Letter => (215.9, 279.4),
+        }
+    }
+}
+
+impl FromStr for Paper {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "a4" => Ok(Paper::A4),
+            "letter" => Ok(Paper::Letter),
+            _ => Err(format!(
+                "unknown paper size {:?} (expected a4 or letter)",
+                s
+            )),
+        }
+    }
+}
+
+/// Ink used for each ear's symbols and curves. Symbol shapes already tell
+/// the ears apart, so every scheme stays readable in black and white.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    /// ASHA convention: red for the right ear, blue for the left.
+    #[default]
+    Asha,
+    /// Black for both ears, for fax and black-and-white printers.
+    Monochrome,
+    /// Vermillion and blue from the Okabe-Ito palette, distinguishable
+    /// with the common forms of colour blindness.
+    ColorBlindSafe,
+}
+
+impl ColorScheme {
+    pub fn ear_color(self, ear: Ear) -> SymbolColor {
+        match (self, ear) {
+            (ColorScheme::Asha, Ear::Right) => SymbolColor::RED,
+            (ColorScheme::Asha, Ear::Left) => SymbolColor::BLUE,
+            (ColorScheme::Monochrome, _) => SymbolColor::BLACK,
+            (ColorScheme::ColorBlindSafe, Ear::Right) => SymbolColor::rgb8(213, 94, 0),
+            (ColorScheme::ColorBlindSafe, Ear::Left) => SymbolColor::rgb8(0, 114, 178),
+        }
+    }
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "asha" => Ok(ColorScheme::Asha),
+            "mono" | "monochrome" => Ok(ColorScheme::Monochrome),
+            "colorblind" | "colourblind" => Ok(ColorScheme::ColorBlindSafe),
+            _ => Err(format!(
+                "unknown color scheme {:?} (expected asha, mono or colorblind)",
+                s
+            )),
+        }
+    }
+}
+
+/// Where each ear's symbol legend goes: a corner inside that ear's
+/// audiogram grid, or nowhere.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LegendPosition {
+    UpperLeft,
+    UpperRight,
+    /// Low frequencies at high levels: rarely reached by thresholds.
+    #[default]
+    LowerLeft,
+    LowerRight,
+    Hidden,
+}
+
+impl FromStr for LegendPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "upper-left" => Ok(LegendPosition::UpperLeft),
+            "upper-right" => Ok(LegendPosition::UpperRight),
+            "lower-left" => Ok(LegendPosition::LowerLeft),
+            "lower-right" => Ok(LegendPosition::LowerRight),
+            "none" => Ok(LegendPosition::Hidden),
+            _ => Err(format!(
+                "unknown legend position {:?} (expected upper-left, upper-right, lower-left, lower-right or none)",
+                s
+            )),
+        }
+    }
+}
+
+/// What happens to notes too long for the footer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NotesOverflow {
+    /// Smaller type, down to 6 pt, then cut with an ellipsis.
+    Shrink,
+    /// Cut with an ellipsis.
+    Ellipsis,
+    /// Continue on extra pages, so nothing is lost.
+    #[default]
+    NextPage,
+}
+
+impl NotesOverflow {
+    fn overflow(self) -> Overflow {
+        match self {
+            NotesOverflow::Shrink => Overflow::Shrink {
+                min_size: FONT_SIZE_MIN_NOTES,
+            },
+            NotesOverflow::Ellipsis => Overflow::Ellipsis,
+            NotesOverflow::NextPage => Overflow::Continue,
+        }
+    }
+}
+
+impl FromStr for NotesOverflow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "shrink" => Ok(NotesOverflow::Shrink),
+            "ellipsis" => Ok(NotesOverflow::Ellipsis),
+            "next-page" => Ok(NotesOverflow::NextPage),
+            _ => Err(format!(
+                "unknown notes overflow {:?} (expected shrink, ellipsis or next-page)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReportOptions {
+    pub lang: Lang,
+    pub paper: Paper,
+    /// Faces the report can be set in: the builtin Helvetica family, which
+    /// only covers the WinAnsi character set, plus any fonts registered.
+    pub fonts: FontRegistry,
+    /// Body text. Fallback families after the first supply characters it
+    /// has no glyph for, such as Greek or geometric symbols in notes.
+    pub text_font: FontSelection,
+    /// Section titles such as "AUDIOMÉTRIE TONALE".
+    pub title_font: FontSelection,
+    /// Embed only the glyphs the report draws rather than whole fonts.
+    pub subset_fonts: bool,
+    /// Draw dashed columns at 750, 1500, 3000 and 6000 Hz.
+    pub inter_octave_lines: bool,
+    pub colors: ColorScheme,
+    pub legend: LegendPosition,
+    pub notes_overflow: NotesOverflow,
+    /// Ranges tympanograms are typed against, shaded on the plot.
+    pub tympanogram_norms: TympanogramNorms,
+}
+
+/// Regular Helvetica text with bold titles.
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            lang: Lang::default(),
+            paper: Paper::default(),
+            fonts: FontRegistry::default(),
+            text_font: FontSelection::new("Helvetica", Weight::REGULAR, Style::Normal),
+            title_font: FontSelection::new("Helvetica", Weight::BOLD, Style::Normal),
+            subset_fonts: true,
+            inter_octave_lines: false,
+            colors: ColorScheme::default(),
+            legend: LegendPosition::default(),
+            notes_overflow: NotesOverflow::default(),
+            tympanogram_norms: TympanogramNorms::default(),
+        }
+    }
+}
+
+/// Lay out the full audiogram page for `eval` on a new document, followed
+/// by extra pages for notes that do not fit in the footer.
+pub fn render(eval: &Evaluation, options: &ReportOptions) -> Result<PdfDocumentReference, Error> {
+    let title = format!(
+        "Audiogram - {} {}",
+        eval.patient.first_name, eval.patient.last_name
+    );
+    let (paper_w, paper_h) = options.paper.size();
+    let (doc, page1, layer1) = PdfDocument::new(title, Mm(paper_w), Mm(paper_h), "Layer 1");
+    let current_layer = doc.get_page(page1).get_layer(layer1);
+
+    let fonts = Fonts::new(options)?;
+    let pdf_fonts = embedded_fonts(eval, options, &fonts)?
+        .iter()
+        .map(|data| match data {
+            FontData::Builtin(font) => doc.add_builtin_font(*font),
+            FontData::TrueType(bytes) => doc.add_external_font(bytes.as_slice()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut notes = draw_page(
+        &mut PdfCanvas::with_fonts(current_layer, pdf_fonts.clone()),
+        &fonts,
+        eval,
+        options,
+    )?;
+    while let Some(rest) = notes {
+        let (page, layer) = doc.add_page(Mm(paper_w), Mm(paper_h), "Layer 1");
+        let layer = doc.get_page(page).get_layer(layer);
+        let mut canvas = PdfCanvas::with_fonts(layer, pdf_fonts.clone());
+        notes = draw_notes_page(&mut canvas, &fonts, eval, &rest, options)?;
+    }
+    Ok(doc)
+}
+
+/// Lay out the full audiogram page for `eval` as a standalone SVG document,
+/// with any extra notes pages stacked below it.
+/// Registered fonts are embedded; the builtin Helvetica faces are left to
+/// the viewer's Helvetica (or closest match), as in PDF.
+pub fn render_svg(eval: &Evaluation, options: &ReportOptions) -> Result<String, Error> {
+    let fonts = Fonts::new(options)?;
+    let (paper_w, paper_h) = options.paper.size();
+    let svg_fonts = options
+        .fonts
+        .faces()
+        .iter()
+        .zip(embedded_fonts(eval, options, &fonts)?)
+        .map(|(face, data)| match data {
+            FontData::Builtin(_) => SvgFont::Family {
+                family: "Helvetica, Arial, sans-serif".to_string(),
+                weight: face.weight.0,
+                italic: face.style == Style::Italic,
+            },
+            FontData::TrueType(bytes) => SvgFont::Embedded(bytes),
+        })
+        .collect();
+    let mut canvas = SvgCanvas::with_fonts(paper_w, paper_h, svg_fonts);
+    let mut notes = draw_page(&mut canvas, &fonts, eval, options)?;
+    while let Some(rest) = notes {
+        canvas.add_page();
+        notes = draw_notes_page(&mut canvas, &fonts, eval, &rest, options)?;
+    }
+    Ok(canvas.finish())
+}
+
+/// The registered faces as they go into the document, in face id order:
+/// with `options.subset_fonts`, TrueType faces are cut down to the
+/// characters the report draws in them, found by laying it out once on a
+/// recording canvas.
+fn embedded_fonts(
+    eval: &Evaluation,
+    options: &ReportOptions,
+    fonts: &Fonts,
+) -> Result<Vec<FontData>, Error> {
+    let faces = options.fonts.faces();
+    if !options.subset_fonts {
+        return Ok(faces.iter().map(|face| face.data.clone()).collect());
+    }
+
+    let mut recording = RecordingCanvas::default();
+    let mut notes = draw_page(&mut recording, fonts, eval, options)?;
+    while let Some(rest) = notes {
+        notes = draw_notes_page(&mut recording, fonts, eval, &rest, options)?;
+    }
+    let characters = recording.characters();
+
+    Ok(faces
+        .iter()
+        .enumerate()
+        .map(|(id, face)| match &face.data {
+            FontData::TrueType(bytes) => {
+                let used = characters.get(&id).cloned().unwrap_or_default();
+                // fonts that cannot be subset go in whole
+                let subset = subset::subset(bytes, &used).unwrap_or_else(|| bytes.clone());
+                FontData::TrueType(subset)
+            }
+            builtin => builtin.clone(),
+        })
+        .collect())
+}
+
+/// Metrics of the faces the report text is set in.
+struct Fonts {
+    text: TextMeasurer,
+    title: TextMeasurer,
+}
+
+impl Fonts {
+    fn new(options: &ReportOptions) -> Result<Self, fonts::Error> {
+        Ok(Fonts {
+            text: options.fonts.measurer(&options.text_font)?,
+            title: options.fonts.measurer(&options.title_font)?,
+        })
+    }
+}
+
+/// Fit the A4 layout onto the paper, centered. Pair with
+/// `restore_graphics_state` once the page is drawn.
+fn begin_page(canvas: &mut dyn Canvas, paper: Paper) {
+    let (paper_w, paper_h) = paper.size();
+    let scale = (paper_w / PAGE_WIDTH).min(paper_h / PAGE_HEIGHT);
+    let offset_x = (paper_w - PAGE_WIDTH * scale) / 2.0;
+    let offset_y = (paper_h - PAGE_HEIGHT * scale) / 2.0;
+    canvas.save_graphics_state();
+    canvas.transform(offset_x, offset_y, scale);
+}
+
+/// The audiogram page. Returns the notes left over for extra pages.
+fn draw_page(
+    canvas: &mut dyn Canvas,
+    fonts: &Fonts,
+    eval: &Evaluation,
+    options: &ReportOptions,
+) -> Result<Option<String>, text::Error> {
+    let labels = options.lang.labels();
+    begin_page(canvas, options.paper);
+
+    // Draw all sections of the audiogram
+    draw_header(canvas, &fonts.text, labels, eval, options.colors)?;
+    draw_tonal_audiometry(canvas, fonts, labels, &eval.audiogram, options)?;
+    draw_evaluation_details(canvas, &fonts.text, labels, eval)?;
+    draw_speech_audiometry(canvas, fonts, labels, &eval.speech, options.colors)?;
+    draw_immitancemetry(canvas, fonts, labels, &eval.immittance, options)?;
+    let notes = draw_footer(canvas, &fonts.text, labels, eval, options)?;
+    draw_main_borders(canvas);
+
+    canvas.restore_graphics_state();
+    Ok(notes)
+}
+
+/// Extra page for `notes` that did not fit on the previous one, headed
+/// with the patient so it can be filed on its own. Returns what is still
+/// left over.
+fn draw_notes_page(
+    canvas: &mut dyn Canvas,
+    fonts: &Fonts,
+    eval: &Evaluation,
+    notes: &str,
+    options: &ReportOptions,
+) -> Result<Option<String>, text::Error> {
+    let labels = options.lang.labels();
+    let font = &fonts.text;
+    begin_page(canvas, options.paper);
+
+    let y_pos = PAGE_HEIGHT - 17.0;
+    draw_centered_title(
+        canvas,
+        &fonts.title,
+        labels.notes_continued,
+        FONT_SIZE_TITLE,
+        y_pos,
+    )?;
+    draw_centered_title(
+        canvas,
+        font,
+        &patient_line(labels, eval),
+        FONT_SIZE_NORMAL,
+        y_pos - 6.0,
+    )?;
+    let rect = BoundingBox {
+        left: MARGIN,
+        bottom: MARGIN,
+        right: PAGE_WIDTH - MARGIN,
+        top: y_pos - 12.0,
+    };
+    let rest = draw_paragraph(canvas, notes, &rect, &notes_style(options), font)?;
+
+    canvas.restore_graphics_state();
+    Ok(rest)
+}
+
+fn notes_style(options: &ReportOptions) -> ParagraphStyle {
+    ParagraphStyle {
+        overflow: options.notes_overflow.overflow(),
+        ..ParagraphStyle::new(FONT_SIZE_NORMAL)
+    }
+}
+
+/// Box spanning `left..right` whose bottom edge is `baseline`, for lines
+/// aligned with [`VAlign::Baseline`].
+fn line_box(left: f32, right: f32, baseline: f32) -> BoundingBox {
+    BoundingBox {
+        left,
+        bottom: baseline,
+        right,
+        top: baseline,
+    }
+}
+
+/// `text` starting at `x` on `baseline`.
+fn draw_text(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    text: &str,
+    font_size: f32,
+    x: f32,
+    baseline: f32,
+) -> Result<(), text::Error> {
+    draw_text_in_box(
+        canvas,
+        text,
+        &line_box(x, x, baseline),
+        HAlign::Left,
+        VAlign::Baseline,
+        font,
+        font_size,
+    )
+}
+
+/// `text` centered between the page margins on `baseline`.
+fn draw_centered_title(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    text: &str,
+    font_size: f32,
+    baseline: f32,
+) -> Result<(), text::Error> {
+    draw_text_in_box(
+        canvas,
+        text,
+        &line_box(MARGIN, PAGE_WIDTH - MARGIN, baseline),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        font_size,
+    )
+}
+
+fn draw_header(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    eval: &Evaluation,
+    colors: ColorScheme,
+) -> Result<(), text::Error> {
+    // --- Top Left: Evaluation Details ---
+    let y_pos = 280.0;
+    draw_text(
+        canvas,
+        font,
+        &format!("{}: {}", labels.evaluation_date, eval.date),
+        FONT_SIZE_NORMAL,
+        MARGIN,
+        y_pos,
+    )?;
+    draw_text(
+        canvas,
+        font,
+        &format!("{}: {}", labels.evaluation_place, eval.place),
+        FONT_SIZE_NORMAL,
+        MARGIN,
+        y_pos - 5.0,
+    )?;
+
+    // --- Center: Patient and Audiologist ---
+    // Names shrink to stay clear of the details on the left and the
+    // tympanogram on the right; a clinician name too long even at the
+    // smallest size is cut with an ellipsis, short of the top margin
+    let column = |bottom: f32, top: f32| BoundingBox {
+        left: 60.0,
+        bottom,
+        right: 150.0,
+        top,
+    };
+    let centered = |font_size| ParagraphStyle {
+        align: HAlign::Center,
+        v_align: VAlign::Baseline,
+        ..ParagraphStyle::new(font_size)
+    };
+    draw_fitted_text(
+        canvas,
+        &eval.clinician.name.to_uppercase(),
+        &column(y_pos, PAGE_HEIGHT - MARGIN),
+        &centered(FONT_SIZE_LARGE_TITLE),
+        FONT_SIZE_TITLE,
+        font,
+    )?;
+    draw_fitted_text(
+        canvas,
+        &patient_line(labels, eval),
+        &column(y_pos - 6.0, y_pos - 6.0),
+        &centered(FONT_SIZE_NORMAL),
+        FONT_SIZE_SMALL,
+        font,
+    )?;
+    draw_centered_title(
+        canvas,
+        font,
+        labels.profession,
+        FONT_SIZE_NORMAL,
+        y_pos - 10.0,
+    )?;
+
+    // --- Top Right: both tympanograms, on the immittance section's axes ---
+    let grid = BoundingBox {
+        left: 160.0,
+        bottom: 275.0,
+        right: 200.0,
+        top: 290.0,
+    };
+    draw_simple_grid(
+        canvas,
+        font,
+        grid.left,
+        grid.bottom,
+        grid.width(),
+        grid.height(),
+        6,
+        5,
+        "-400",
+        "200",
+        "0",
+        "2.5",
+    )?;
+    for ear in [Ear::Right, Ear::Left] {
+        if let Some(tympanogram) = &eval.immittance.ear(ear).tympanogram {
+            draw_tympanogram_curve(canvas, &grid, tympanogram, colors.ear_color(ear));
+        }
+    }
+    Ok(())
+}
+
+/// "Jane DOE, 1950-01-01 (75 years old)"
+fn patient_line(labels: &Labels, eval: &Evaluation) -> String {
+    format!(
+        "{} {}, {} ({} {})",
+        eval.patient.first_name,
+        eval.patient.last_name.to_uppercase(),
+        eval.patient.date_of_birth,
+        eval.patient_age(),
+        labels.years_old
+    )
+}
+
+fn draw_tonal_audiometry(
+    canvas: &mut dyn Canvas,
+    fonts: &Fonts,
+    labels: &Labels,
+    audiogram: &Audiogram,
+    options: &ReportOptions,
+) -> Result<(), text::Error> {
+    let font = &fonts.text;
+    let y_start = 255.0;
+    let grid_height = 70.0;
+    let grid_width_single = 80.0;
+    let center_x = PAGE_WIDTH / 2.0;
+
+    // --- Main Title ---
+    draw_centered_title(
+        canvas,
+        &fonts.title,
+        labels.tonal_audiometry,
+        FONT_SIZE_TITLE,
+        y_start,
+    )?;
+
+    let grid_y = y_start - 10.0;
+
+    // --- Grids ---
+    // Frequencies (X-axis), on a log2 scale that extends past 8000 Hz when
+    // extended high frequencies were tested
+    let max_frequency = if audiogram.has_extended_high_frequencies() {
+        EXTENDED_HIGH_FREQUENCIES[EXTENDED_HIGH_FREQUENCIES.len() - 1]
+    } else {
+        OCTAVE_FREQUENCIES[OCTAVE_FREQUENCIES.len() - 1]
+    };
+
+    // Right Ear Grid (left side of page)
+    let left_grid_start_x = center_x - grid_width_single - 10.0;
+    let right_grid = AudiogramGrid {
+        left: left_grid_start_x,
+        top: grid_y,
+        width: grid_width_single,
+        height: grid_height,
+        max_frequency,
+    };
+    draw_frequency_columns(canvas, font, &right_grid, options.inter_octave_lines)?;
+
+    // Left Ear Grid (right side of page)
+    let right_grid_start_x = center_x + 10.0;
+    let left_grid = AudiogramGrid {
+        left: right_grid_start_x,
+        ..right_grid
+    };
+    draw_frequency_columns(canvas, font, &left_grid, options.inter_octave_lines)?;
+
+    // --- Ear Titles ---, centered over each grid
+    for (grid, title) in [
+        (&right_grid, labels.right_ear),
+        (&left_grid, labels.left_ear),
+    ] {
+        draw_text_in_box(
+            canvas,
+            title,
+            &line_box(grid.left, grid.left + grid.width, y_start - 7.0),
+            HAlign::Center,
+            VAlign::Baseline,
+            font,
+            FONT_SIZE_NORMAL,
+        )?;
+    }
+
+    // dBHL (Y-axis) and horizontal lines, with the labels centered in the
+    // gap between the grids
+    let gutter = |baseline| {
+        line_box(
+            left_grid_start_x + grid_width_single,
+            right_grid_start_x,
+            baseline,
+        )
+    };
+    let rows = (MAX_LEVEL - MIN_LEVEL) / 10; // 14 labels from -10 to 120
+    let y_spacing = grid_height / rows as f32;
+    for i in 0..=rows {
+        let y = grid_y - (i as f32 * y_spacing);
+        let db_label = MIN_LEVEL + (i * 10);
+        draw_text_in_box(
+            canvas,
+            &format!("{}", db_label),
+            &gutter(y - 1.0),
+            HAlign::Center,
+            VAlign::Baseline,
+            font,
+            FONT_SIZE_SMALL,
+        )?;
+        canvas.line(
+            (left_grid_start_x, y),
+            (right_grid_start_x + grid_width_single, y),
+        );
+    }
+    draw_text_in_box(
+        canvas,
+        "dBHL",
+        &gutter(grid_y + 2.0),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        FONT_SIZE_SMALL,
+    )?;
+
+    // --- Thresholds ---
+    let (right_color, left_color) = (
+        options.colors.ear_color(Ear::Right),
+        options.colors.ear_color(Ear::Left),
+    );
+    draw_thresholds(
+        canvas,
+        &right_grid,
+        Ear::Right,
+        &audiogram.right,
+        right_color,
+    );
+    draw_thresholds(canvas, &left_grid, Ear::Left, &audiogram.left, left_color);
+
+    // --- Legends ---
+    if options.legend != LegendPosition::Hidden {
+        draw_legend(
+            canvas,
+            font,
+            labels,
+            &right_grid,
+            options.legend,
+            Ear::Right,
+            &audiogram.right,
+            right_color,
+        )?;
+        draw_legend(
+            canvas,
+            font,
+            labels,
+            &left_grid,
+            options.legend,
+            Ear::Left,
+            &audiogram.left,
+            left_color,
+        )?;
+    }
+    Ok(())
+}
+
+/// Plotting area of one ear's audiogram, in page millimeters.
+#[derive(Copy, Clone)]
+struct AudiogramGrid {
+    left: f32,
+    top: f32,
+    width: f32,
+    height: f32,
+    /// Frequency at the right edge; the left edge is the lowest octave.
+    max_frequency: u32,
+}
+
+impl AudiogramGrid {
+    /// Frequencies are placed on a log2 scale, so every octave is the same
+    /// width and inter-octave frequencies fall near halfway between, at
+    /// log2(1.5) of their octave.
+    fn x_for(&self, frequency: u32) -> Option<f32> {
+        let min_frequency = OCTAVE_FREQUENCIES[0];
+        if !(min_frequency..=self.max_frequency).contains(&frequency) {
+            return None;
+        }
+        let octaves = (frequency as f32 / min_frequency as f32).log2();
+        let span = (self.max_frequency as f32 / min_frequency as f32).log2();
+        Some(self.left + octaves / span * self.width)
+    }
+
+    fn y_for(&self, level: i32) -> f32 {
+        self.top - (level - MIN_LEVEL) as f32 * self.height / (MAX_LEVEL - MIN_LEVEL) as f32
+    }
+
+    fn point_for(&self, threshold: &Threshold) -> Option<(f32, f32)> {
+        Some((
+            self.x_for(threshold.frequency)?,
+            self.y_for(threshold.level),
+        ))
+    }
+}
+
+/// Vertical frequency lines and their labels for one ear's grid. Octaves
+/// are labelled on the first row above the grid; inter-octave and extended
+/// high frequencies share a second row in a smaller size, in kHz above
+/// 8000 Hz.
+fn draw_frequency_columns(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    grid: &AudiogramGrid,
+    inter_octave_lines: bool,
+) -> Result<(), text::Error> {
+    let bottom = grid.top - grid.height;
+
+    // Numbers are centered on their line; the unit goes after the rightmost
+    // octave label
+    for freq in OCTAVE_FREQUENCIES {
+        let x = grid.x_for(freq).unwrap();
+        let number = freq.to_string();
+        let left = x - font.width(&number, FONT_SIZE_SMALL)? / 2.0;
+        let label = if freq == grid.max_frequency {
+            format!("{} Hz", number)
+        } else {
+            number
+        };
+        draw_text_in_box(
+            canvas,
+            &label,
+            &line_box(left, left, grid.top + 2.0),
+            HAlign::Left,
+            VAlign::Baseline,
+            font,
+            FONT_SIZE_SMALL,
+        )?;
+        canvas.line((x, grid.top), (x, bottom));
+    }
+
+    let ehf = EXTENDED_HIGH_FREQUENCIES
+        .iter()
+        .filter(|f| **f <= grid.max_frequency);
+    for freq in ehf {
+        let x = grid.x_for(*freq).unwrap();
+        draw_small_frequency_label(canvas, font, *freq, x, grid.top + 5.5)?;
+        canvas.line((x, grid.top), (x, bottom));
+    }
+
+    if inter_octave_lines {
+        canvas.save_graphics_state();
+        canvas.set_line_dash(Some((2.0, 2.0)));
+        for freq in INTER_OCTAVE_FREQUENCIES {
+            let x = grid.x_for(freq).unwrap();
+            draw_small_frequency_label(canvas, font, freq, x, grid.top + 5.5)?;
+            canvas.line((x, grid.top), (x, bottom));
+        }
+        canvas.restore_graphics_state();
+    }
+    Ok(())
+}
+
+/// "750", "1500", "10k", "12.5k", centered on `x`.
+fn draw_small_frequency_label(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    frequency: u32,
+    x: f32,
+    y: f32,
+) -> Result<(), text::Error> {
+    let label = if frequency >= 10000 {
+        format!("{}k", frequency as f32 / 1000.0)
+    } else {
+        frequency.to_string()
+    };
+    draw_text_in_box(
+        canvas,
+        &label,
+        &line_box(x, x, y),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        FONT_SIZE_TINY,
+    )
+}
+
+/// ASHA audiogram symbol for a threshold.
+fn threshold_symbol(ear: Ear, conduction: Conduction, masked: bool) -> Symbol {
+    match (conduction, ear, masked) {
+        (Conduction::Air, Ear::Right, false) => Symbol::Circle,
+        (Conduction::Air, Ear::Right, true) => Symbol::Triangle,
+        (Conduction::Air, Ear::Left, false) => Symbol::X,
+        (Conduction::Air, Ear::Left, true) => Symbol::Square,
+        (Conduction::Bone, Ear::Right, false) => Symbol::Less,
+        (Conduction::Bone, Ear::Right, true) => Symbol::LeftBracket,
+        (Conduction::Bone, Ear::Left, false) => Symbol::Greater,
+        (Conduction::Bone, Ear::Left, true) => Symbol::RightBracket,
+    }
+}
+
+/// Plot one ear's thresholds. Air conduction symbols are joined by a line
+/// that stops at each symbol's edge; bone conduction symbols are not joined
+/// and sit beside the frequency line, on the side of the tested ear.
+fn draw_thresholds(
+    canvas: &mut dyn Canvas,
+    grid: &AudiogramGrid,
+    ear: Ear,
+    thresholds: &EarThresholds,
+    color: SymbolColor,
+) {
+    let style = SymbolStyle::new(THRESHOLD_SYMBOL_SIZE, color);
+    let size = pt_to_mm(THRESHOLD_SYMBOL_SIZE);
+
+    // The curve's color and line width stay local to this ear
+    canvas.save_graphics_state();
+
+    // The curve is broken on either side of a no-response threshold
+    canvas.set_outline_color(color);
+    canvas.set_outline_thickness(0.75);
+    for pair in thresholds.air.windows(2) {
+        if pair[0].no_response || pair[1].no_response {
+            continue;
+        }
+        let (Some((x0, y0)), Some((x1, y1))) = (grid.point_for(&pair[0]), grid.point_for(&pair[1]))
+        else {
+            continue;
+        };
+        draw_symbol_link(canvas, (x0, y0), (x1, y1), size);
+    }
+
+    for (conduction, list) in [
+        (Conduction::Air, &thresholds.air),
+        (Conduction::Bone, &thresholds.bone),
+    ] {
+        for t in list {
+            let Some((x, y)) = grid.point_for(t) else {
+                continue;
+            };
+            let x = match (conduction, ear) {
+                (Conduction::Air, _) => x,
+                (Conduction::Bone, Ear::Right) => x - size / 2.0,
+                (Conduction::Bone, Ear::Left) => x + size / 2.0,
+            };
+            let symbol = threshold_symbol(ear, conduction, t.masked);
+            draw_symbol(canvas, symbol, (Mm(x), Mm(y)), &style);
+            if t.no_response {
+                draw_no_response_arrow(canvas, ear, (x, y), color);
+            }
+        }
+    }
+
+    canvas.restore_graphics_state();
+}
+
+/// Line joining the symbols of `size` centered on `from` and `to`, stopped
+/// at their edges; nothing when they overlap.
+fn draw_symbol_link(canvas: &mut dyn Canvas, from: (f32, f32), to: (f32, f32), size: f32) {
+    let ((x0, y0), (x1, y1)) = (from, to);
+    let len = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+    if len <= size {
+        return;
+    }
+    let (ux, uy) = ((x1 - x0) / len * size / 2.0, (y1 - y0) / len * size / 2.0);
+    canvas.line((x0 + ux, y0 + uy), (x1 - ux, y1 - uy));
+}
+
+/// No-response arrow hanging from the lower outer corner of the symbol
+/// centered on `center`: down-left for the right ear, down-right for the
+/// left ear.
+fn draw_no_response_arrow(
+    canvas: &mut dyn Canvas,
+    ear: Ear,
+    center: (f32, f32),
+    color: SymbolColor,
+) {
+    // the tail sits on the symbol outline, 45 degrees below center
+    let corner = pt_to_mm(THRESHOLD_SYMBOL_SIZE) / 2.0 * std::f32::consts::FRAC_1_SQRT_2;
+    let half = pt_to_mm(NO_RESPONSE_ARROW_SIZE) / 2.0;
+    let (cx, cy) = center;
+    let (symbol, center) = match ear {
+        Ear::Right => (
+            Symbol::ArrowDownLeftFilled,
+            (cx - corner - half, cy - corner - half),
+        ),
+        Ear::Left => (
+            Symbol::ArrowDownRightFilled,
+            (cx + corner + half, cy - corner - half),
+        ),
+    };
+    draw_symbol(
+        canvas,
+        symbol,
+        (Mm(center.0), Mm(center.1)),
+        &SymbolStyle::new(NO_RESPONSE_ARROW_SIZE, color),
+    );
+}
+
+#[derive(Debug, PartialEq)]
+enum LegendRow {
+    Heading(&'static str),
+    Symbol(Symbol, &'static str),
+    NoResponse,
+}
+
+/// Legend rows for one ear, following the order of the ASHA chart: air
+/// before bone, unmasked before masked. Only symbols that appear on the
+/// ear's audiogram are listed.
+fn legend_rows(labels: &Labels, ear: Ear, thresholds: &EarThresholds) -> Vec<LegendRow> {
+    let mut rows = Vec::new();
+    for (conduction, list, heading) in [
+        (Conduction::Air, &thresholds.air, labels.air_conduction),
+        (Conduction::Bone, &thresholds.bone, labels.bone_conduction),
+    ] {
+        let used = [false, true].map(|masked| list.iter().any(|t| t.masked == masked));
+        if !used.contains(&true) {
+            continue;
+        }
+        rows.push(LegendRow::Heading(heading));
+        for (masked, label) in [(false, labels.unmasked), (true, labels.masked)] {
+            if used[masked as usize] {
+                rows.push(LegendRow::Symbol(
+                    threshold_symbol(ear, conduction, masked),
+                    label,
+                ));
+            }
+        }
+    }
+    if thresholds
+        .air
+        .iter()
+        .chain(&thresholds.bone)
+        .any(|t| t.no_response)
+    {
+        rows.push(LegendRow::NoResponse);
+    }
+    rows
+}
+
+/// Boxed legend of the symbols used for one ear, inside a corner of that
+/// ear's grid.
+#[allow(clippy::too_many_arguments)]
+fn draw_legend(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    grid: &AudiogramGrid,
+    position: LegendPosition,
+    ear: Ear,
+    thresholds: &EarThresholds,
+    color: SymbolColor,
+) -> Result<(), text::Error> {
+    const INSET: f32 = 1.5;
+    const WIDTH: f32 = 27.0;
+    const ROW_HEIGHT: f32 = 3.5;
+    const SYMBOL_X: f32 = 3.0;
+    const TEXT_X: f32 = 6.0;
+
+    let rows = legend_rows(labels, ear, thresholds);
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let height = rows.len() as f32 * ROW_HEIGHT + 1.0;
+    let bottom = grid.top - grid.height;
+    let (left, top) = match position {
+        LegendPosition::UpperLeft => (grid.left + INSET, grid.top - INSET),
+        LegendPosition::UpperRight => (grid.left + grid.width - INSET - WIDTH, grid.top - INSET),
+        LegendPosition::LowerLeft => (grid.left + INSET, bottom + INSET + height),
+        LegendPosition::LowerRight => (
+            grid.left + grid.width - INSET - WIDTH,
+            bottom + INSET + height,
+        ),
+        LegendPosition::Hidden => return Ok(()),
+    };
+
+    canvas.save_graphics_state();
+
+    // Opaque background so grid lines don't run through the text
+    canvas.set_fill_color(SymbolColor::Gray(1.0));
+    canvas.set_outline_color(SymbolColor::BLACK);
+    canvas.set_outline_thickness(0.3);
+    canvas.rect(left, top - height, left + WIDTH, top, Paint::FillStroke);
+    canvas.set_fill_color(SymbolColor::BLACK);
+
+    let style = SymbolStyle::new(LEGEND_SYMBOL_SIZE, color);
+    for (i, row) in rows.iter().enumerate() {
+        // rows are centered on `y`; text sits a little below to look centered
+        let y = top - 0.5 - (i as f32 + 0.5) * ROW_HEIGHT;
+        let text_y = y - 0.9;
+        match row {
+            LegendRow::Heading(text) => {
+                draw_text(canvas, font, text, FONT_SIZE_TINY, left + 1.0, text_y)?;
+            }
+            LegendRow::Symbol(symbol, text) => {
+                draw_symbol(canvas, *symbol, (Mm(left + SYMBOL_X), Mm(y)), &style);
+                draw_text(canvas, font, text, FONT_SIZE_TINY, left + TEXT_X, text_y)?;
+            }
+            LegendRow::NoResponse => {
+                let symbol = match ear {
+                    Ear::Right => Symbol::ArrowDownLeftFilled,
+                    Ear::Left => Symbol::ArrowDownRightFilled,
+                };
+                draw_symbol(canvas, symbol, (Mm(left + SYMBOL_X), Mm(y)), &style);
+                draw_text(
+                    canvas,
+                    font,
+                    labels.no_response_legend,
+                    FONT_SIZE_TINY,
+                    left + TEXT_X,
+                    text_y,
+                )?;
+            }
+        }
+    }
+
+    canvas.restore_graphics_state();
+    Ok(())
+}
+
+fn draw_evaluation_details(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    eval: &Evaluation,
+) -> Result<(), text::Error> {
+    let y_start = 175.0;
+    let x_start = MARGIN;
+
+    // --- Validity & Stimuli ---
+    draw_text(
+        canvas,
+        font,
+        labels.validity,
+        FONT_SIZE_SMALL,
+        x_start,
+        y_start,
+    )?;
+    let validity = eval.validity;
+    draw_checkbox(
+        canvas,
+        font,
+        x_start,
+        y_start - 5.0,
+        labels.validity_good,
+        validity == Validity::Good,
+    )?;
+    draw_checkbox(
+        canvas,
+        font,
+        x_start,
+        y_start - 10.0,
+        labels.validity_fair,
+        validity == Validity::Fair,
+    )?;
+    draw_checkbox(
+        canvas,
+        font,
+        x_start,
+        y_start - 15.0,
+        labels.validity_poor,
+        validity == Validity::Poor,
+    )?;
+
+    draw_text(
+        canvas,
+        font,
+        labels.stimuli,
+        FONT_SIZE_SMALL,
+        x_start + 30.0,
+        y_start,
+    )?;
+    draw_checkbox(
+        canvas,
+        font,
+        x_start + 30.0,
+        y_start - 5.0,
+        labels.pure_tones,
+        eval.stimulus == Stimulus::PureTone,
+    )?;
+    draw_checkbox(
+        canvas,
+        font,
+        x_start + 30.0,
+        y_start - 10.0,
+        labels.warble_tones,
+        eval.stimulus == Stimulus::Warble,
+    )?;
+
+    // --- Evaluation Method ---
+    draw_text(
+        canvas,
+        font,
+        labels.method,
+        FONT_SIZE_SMALL,
+        x_start + 70.0,
+        y_start,
+    )?;
+    draw_checkbox(
+        canvas,
+        font,
+        x_start + 70.0,
+        y_start - 5.0,
+        &eval.method,
+        true,
+    )?;
+
+    // --- Pure Tone Averages ---
+    let y_pta = y_start - 20.0;
+    draw_text(
+        canvas,
+        font,
+        labels.pure_tone_averages,
+        FONT_SIZE_NORMAL,
+        x_start,
+        y_pta,
+    )?;
+
+    // One box per ear, right ear on the left
+    for (ear, left) in [(Ear::Right, x_start), (Ear::Left, x_start + 100.0)] {
+        let cell = BoundingBox {
+            left,
+            bottom: y_pta - 15.0,
+            right: left + 80.0,
+            top: y_pta - 2.0,
+        };
+        canvas.rect(cell.left, cell.bottom, cell.right, cell.top, Paint::Stroke);
+        draw_text_in_box(
+            canvas,
+            &pure_tone_averages(labels, eval, ear),
+            &cell,
+            HAlign::Center,
+            VAlign::Middle,
+            font,
+            FONT_SIZE_NORMAL,
+        )?;
+    }
+    Ok(())
+}
+
+fn pure_tone_averages(labels: &Labels, eval: &Evaluation, ear: Ear) -> String {
+    let thresholds = eval.audiogram.ear(ear);
+    let fmt = |v: Option<f32>, decimals: usize| match v {
+        Some(v) => format!("{:.*}", decimals, v),
+        None => "-".to_string(),
+    };
+    format!(
+        "{}: {}   {}: {}   {}: {}",
+        labels.pta3,
+        fmt(thresholds.pta3(), 0),
+        labels.pta4,
+        fmt(thresholds.pta4(), 2),
+        labels.fletcher,
+        fmt(thresholds.fletcher(), 0)
+    )
+}
+
+fn draw_speech_audiometry(
+    canvas: &mut dyn Canvas,
+    fonts: &Fonts,
+    labels: &Labels,
+    speech: &Speech,
+    colors: ColorScheme,
+) -> Result<(), text::Error> {
+    let font = &fonts.text;
+    let y_start = 145.0;
+    let x_center = PAGE_WIDTH / 2.0;
+
+    draw_centered_title(
+        canvas,
+        &fonts.title,
+        labels.speech_audiometry,
+        FONT_SIZE_TITLE,
+        y_start,
+    )?;
+
+    // The same tables for both ears, the PI-PB chart right of them
+    for (ear, x, title) in [
+        (Ear::Right, MARGIN, labels.right_ear),
+        (Ear::Left, x_center + 2.0, labels.left_ear),
+    ] {
+        let y = y_start - 5.0;
+        draw_speech_table(canvas, font, labels, x, y, title, speech.ear(ear))?;
+        let chart = BoundingBox {
+            left: x + 65.0,
+            bottom: y - 33.0,
+            right: x + 88.0,
+            top: y - 5.0,
+        };
+        draw_pi_chart(
+            canvas,
+            font,
+            labels,
+            &chart,
+            ear,
+            speech.ear(ear),
+            colors.ear_color(ear),
+        )?;
+    }
+    Ok(())
+}
+
+fn draw_immitancemetry(
+    canvas: &mut dyn Canvas,
+    fonts: &Fonts,
+    labels: &Labels,
+    immittance: &Immittance,
+    options: &ReportOptions,
+) -> Result<(), text::Error> {
+    let font = &fonts.text;
+    let y_start = 85.0;
+    let x_center = PAGE_WIDTH / 2.0;
+
+    draw_centered_title(
+        canvas,
+        &fonts.title,
+        labels.immittance,
+        FONT_SIZE_TITLE,
+        y_start,
+    )?;
+
+    // The tympanogram goes left of the values read off it
+    for (ear, x, title) in [
+        (Ear::Right, MARGIN, labels.right_ear),
+        (Ear::Left, x_center + 2.0, labels.left_ear),
+    ] {
+        let y = y_start - 5.0;
+        draw_immitance_panel(canvas, font, labels, x, y, title, immittance.ear(ear))?;
+        let grid = BoundingBox {
+            left: x + 9.0,
+            bottom: y - 27.0,
+            right: x + 53.0,
+            top: y - 8.0,
+        };
+        draw_tympanogram(
+            canvas,
+            font,
+            labels,
+            &grid,
+            immittance.ear(ear).tympanogram.as_ref(),
+            options.colors.ear_color(ear),
+            &options.tympanogram_norms,
+        )?;
+    }
+    Ok(())
+}
+
+/// Notes and signature. Returns the notes that did not fit, when they
+/// continue on another page.
+fn draw_footer(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    eval: &Evaluation,
+    options: &ReportOptions,
+) -> Result<Option<String>, text::Error> {
+    let y_start = 25.0;
+
+    // Notes section: wrapped beside the heading, down to the bottom margin,
+    // or a line to write on when there are none
+    draw_text(
+        canvas,
+        font,
+        &format!("{}:", labels.notes),
+        FONT_SIZE_NORMAL,
+        MARGIN,
+        y_start,
+    )?;
+    let notes = eval.notes.trim();
+    let mut rest = None;
+    if notes.is_empty() {
+        canvas.line((MARGIN + 12.0, y_start - 1.0), (120.0, y_start - 1.0));
+    } else {
+        let ascent = font.measure("", FONT_SIZE_NORMAL)?.ascent;
+        let rect = BoundingBox {
+            left: MARGIN + 13.0,
+            bottom: MARGIN / 2.0,
+            right: 135.0,
+            top: y_start + ascent,
+        };
+        rest = draw_paragraph(canvas, notes, &rect, &notes_style(options), font)?;
+    }
+
+    // Signature area
+    let sig_x = 140.0;
+    draw_text_in_box(
+        canvas,
+        labels.member,
+        &line_box(sig_x, sig_x + 30.0, y_start),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        FONT_SIZE_SMALL,
+    )?;
+    canvas.rect(
+        sig_x,
+        y_start - 15.0,
+        sig_x + 30.0,
+        y_start - 2.0,
+        Paint::Stroke,
+    ); // Logo placeholder
+    let signature = BoundingBox {
+        left: sig_x,
+        bottom: y_start - 20.0,
+        right: PAGE_WIDTH - MARGIN,
+        top: y_start - 20.0,
+    };
+    draw_fitted_text(
+        canvas,
+        &format!("{}, {}", eval.clinician.name, eval.clinician.credentials),
+        &signature,
+        &ParagraphStyle {
+            v_align: VAlign::Baseline,
+            ..ParagraphStyle::new(FONT_SIZE_NORMAL)
+        },
+        FONT_SIZE_SMALL,
+        font,
+    )?;
+    draw_text(
+        canvas,
+        font,
+        &format!("{} #{}", labels.signature_title, eval.clinician.licence),
+        FONT_SIZE_NORMAL,
+        sig_x,
+        y_start - 25.0,
+    )?;
+    Ok(rest)
+}
+
+// ---- HELPER FUNCTIONS ----
+
+fn draw_main_borders(canvas: &mut dyn Canvas) {
+    // A simple border around major sections can be added here if desired
+    // Example: Border around the tonal audiometry section
+    canvas.rect(MARGIN, 180.0, PAGE_WIDTH - MARGIN, 260.0, Paint::Stroke);
+    // Add other borders as needed...
+}
+
+/// Grid of `v_lines` by `h_lines` cells with its bottom-left corner at
+/// (`x`, `y`), labelled with the ends of its axes: `x_min` and `x_max`
+/// under the left and right edges, `y_min` and `y_max` left of the bottom
+/// and top edges.
+#[allow(clippy::too_many_arguments)]
+fn draw_simple_grid(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    v_lines: usize,
+    h_lines: usize,
+    x_min: &str,
+    x_max: &str,
+    y_min: &str,
+    y_max: &str,
+) -> Result<(), text::Error> {
+    // Boundary
+    canvas.rect(x, y, x + w, y + h, Paint::Stroke);
+
+    // Vertical Lines
+    for i in 1..v_lines {
+        let lx = x + (i as f32 * w / v_lines as f32);
+        canvas.line((lx, y), (lx, y + h));
+    }
+    // Horizontal Lines
+    for i in 1..h_lines {
+        let ly = y + (i as f32 * h / h_lines as f32);
+        canvas.line((x, ly), (x + w, ly));
+    }
+
+    // Labels
+    for (text, baseline) in [(y_min, y), (y_max, y + h - 2.0)] {
+        draw_text_in_box(
+            canvas,
+            text,
+            &line_box(x - 8.0, x - 0.5, baseline),
+            HAlign::Right,
+            VAlign::Baseline,
+            font,
+            FONT_SIZE_TINY,
+        )?;
+    }
+    for (text, align) in [(x_min, HAlign::Left), (x_max, HAlign::Right)] {
+        draw_text_in_box(
+            canvas,
+            text,
+            &line_box(x, x + w, y - 2.5),
+            align,
+            VAlign::Baseline,
+            font,
+            FONT_SIZE_TINY,
+        )?;
+    }
+    Ok(())
+}
+
+fn draw_checkbox(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    x: f32,
+    y: f32,
+    text: &str,
+    checked: bool,
+) -> Result<(), text::Error> {
+    canvas.rect(x, y, x + 3.0, y + 3.0, Paint::Stroke);
+    if checked {
+        // Draw an 'X'
+        canvas.line((x, y), (x + 3.0, y + 3.0));
+        canvas.line((x, y + 3.0), (x + 3.0, y));
+    }
+    draw_text(canvas, font, text, FONT_SIZE_NORMAL, x + 5.0, y)
+}
+
+/// Title centered over a 90 mm wide ear panel at `x`, on `baseline`.
+fn draw_panel_title(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    title: &str,
+    x: f32,
+    baseline: f32,
+) -> Result<(), text::Error> {
+    draw_text_in_box(
+        canvas,
+        title,
+        &line_box(x, x + 90.0, baseline),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        FONT_SIZE_NORMAL,
+    )
+}
+
+fn draw_speech_table(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    x: f32,
+    y: f32,
+    title: &str,
+    speech: &EarSpeech,
+) -> Result<(), text::Error> {
+    // leaves the right of the panel to the PI-PB chart
+    const TABLE_WIDTH: f32 = 56.0;
+
+    draw_panel_title(canvas, font, title, x, y)?;
+    canvas.rect(x, y - 45.0, x + 90.0, y + 5.0, Paint::Stroke);
+    let level = |level: Option<i32>| level.map(|l| l.to_string()).unwrap_or_default();
+    let snr = |snr: Option<f32>| snr.map(|s| format!("{:.1}", s)).unwrap_or_default();
+
+    draw_text(
+        canvas,
+        font,
+        labels.speech_thresholds,
+        FONT_SIZE_SMALL,
+        x + 2.0,
+        y - 4.5,
+    )?;
+    draw_result_table(
+        canvas,
+        font,
+        x + 2.0,
+        y - 6.0,
+        TABLE_WIDTH,
+        &[labels.srt, labels.sdt, labels.mcl, labels.ucl],
+        &[
+            level(speech.srt),
+            level(speech.sdt),
+            level(speech.mcl),
+            level(speech.ucl),
+        ],
+    )?;
+
+    draw_text(
+        canvas,
+        font,
+        labels.word_recognition,
+        FONT_SIZE_SMALL,
+        x + 2.0,
+        y - 17.5,
+    )?;
+    let wr = speech.word_recognition.as_ref();
+    let modality = wr.and_then(|wr| wr.modality).map(|m| match m {
+        Modality::LiveVoice => labels.live_voice,
+        Modality::Recorded => labels.recorded,
+    });
+    draw_result_table(
+        canvas,
+        font,
+        x + 2.0,
+        y - 19.0,
+        TABLE_WIDTH,
+        &[
+            labels.score,
+            labels.level,
+            labels.list,
+            labels.masking,
+            labels.modality,
+        ],
+        &[
+            wr.map(|wr| format!("{:.0}", wr.score)).unwrap_or_default(),
+            level(wr.map(|wr| wr.level)),
+            wr.and_then(|wr| wr.list.clone()).unwrap_or_default(),
+            level(wr.and_then(|wr| wr.masking)),
+            modality.unwrap_or_default().to_string(),
+        ],
+    )?;
+
+    draw_text(
+        canvas,
+        font,
+        labels.speech_in_noise,
+        FONT_SIZE_SMALL,
+        x + 2.0,
+        y - 30.5,
+    )?;
+    let sin = speech.in_noise.as_ref();
+    let category = sin.and_then(|sin| sin.category()).map(|c| match c {
+        SnrLossCategory::Normal => labels.snr_normal,
+        SnrLossCategory::Mild => labels.snr_mild,
+        SnrLossCategory::Moderate => labels.snr_moderate,
+        SnrLossCategory::Severe => labels.snr_severe,
+    });
+    draw_result_table(
+        canvas,
+        font,
+        x + 2.0,
+        y - 32.0,
+        TABLE_WIDTH,
+        &[
+            labels.noise_test,
+            labels.snr50,
+            labels.snr_loss,
+            labels.snr_loss_category,
+        ],
+        &[
+            sin.map(|sin| sin.test.name().to_string())
+                .unwrap_or_default(),
+            snr(sin.and_then(|sin| sin.snr50)),
+            snr(sin.and_then(|sin| sin.snr_loss)),
+            category.unwrap_or_default().to_string(),
+        ],
+    )
+}
+
+/// Word-recognition score against presentation level (the PI-PB
+/// function) on a grid filling `grid`, with the rollover index beneath.
+fn draw_pi_chart(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    grid: &BoundingBox,
+    ear: Ear,
+    speech: &EarSpeech,
+    color: SymbolColor,
+) -> Result<(), text::Error> {
+    let size = pt_to_mm(PI_SYMBOL_SIZE);
+
+    draw_simple_grid(
+        canvas,
+        font,
+        grid.left,
+        grid.bottom,
+        grid.width(),
+        grid.height(),
+        6,
+        5,
+        "0",
+        "120",
+        "0",
+        "100",
+    )?;
+    let baseline = grid.bottom - 2.5;
+    draw_text_in_box(
+        canvas,
+        "dB HL",
+        &line_box(grid.left, grid.right, baseline),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        FONT_SIZE_TINY,
+    )?;
+
+    let points: Vec<(f32, f32)> = speech
+        .pi_function()
+        .iter()
+        .map(|p| {
+            // MIN_LEVEL is below the chart's 0 dB HL
+            let level = p.level.clamp(0, MAX_LEVEL) as f32 / MAX_LEVEL as f32;
+            (
+                grid.left + level * grid.width(),
+                grid.bottom + p.score / 100.0 * grid.height(),
+            )
+        })
+        .collect();
+    canvas.save_graphics_state();
+    canvas.set_outline_color(color);
+    canvas.set_outline_thickness(0.5);
+    for pair in points.windows(2) {
+        draw_symbol_link(canvas, pair[0], pair[1], size);
+    }
+    canvas.restore_graphics_state();
+    let style = SymbolStyle::new(PI_SYMBOL_SIZE, color);
+    let symbol = threshold_symbol(ear, Conduction::Air, false);
+    for &(x, y) in &points {
+        draw_symbol(canvas, symbol, (Mm(x), Mm(y)), &style);
+    }
+
+    if let Some(rollover) = speech.rollover_index() {
+        draw_text_in_box(
+            canvas,
+            &format!("{}: {:.2}", labels.rollover_index, rollover),
+            &line_box(grid.left, grid.right, baseline - 4.0),
+            HAlign::Center,
+            VAlign::Baseline,
+            font,
+            FONT_SIZE_SMALL,
+        )?;
+    }
+    Ok(())
+}
+
+/// Table of test results `width` mm wide hanging from `top`: a row of
+/// column headings over a row of values, left empty for tests not done.
+fn draw_result_table(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    x: f32,
+    top: f32,
+    width: f32,
+    headings: &[&str],
+    values: &[String],
+) -> Result<(), text::Error> {
+    const HEADING_HEIGHT: f32 = 3.5;
+    const VALUE_HEIGHT: f32 = 4.5;
+    let divider = top - HEADING_HEIGHT;
+    let bottom = divider - VALUE_HEIGHT;
+    let column = width / headings.len() as f32;
+
+    canvas.rect(x, bottom, x + width, top, Paint::Stroke);
+    canvas.line((x, divider), (x + width, divider));
+    let heading_style = ParagraphStyle {
+        align: HAlign::Center,
+        v_align: VAlign::Middle,
+        ..ParagraphStyle::new(FONT_SIZE_SMALL)
+    };
+    let value_style = ParagraphStyle {
+        align: HAlign::Center,
+        v_align: VAlign::Middle,
+        ..ParagraphStyle::new(FONT_SIZE_NORMAL)
+    };
+    for (i, (heading, value)) in headings.iter().zip(values).enumerate() {
+        let left = x + i as f32 * column;
+        if i > 0 {
+            canvas.line((left, bottom), (left, top));
+        }
+        let heading_cell = BoundingBox {
+            left: left + 0.5,
+            bottom: divider,
+            right: left + column - 0.5,
+            top,
+        };
+        draw_fitted_text(
+            canvas,
+            heading,
+            &heading_cell,
+            &heading_style,
+            FONT_SIZE_TINY,
+            font,
+        )?;
+        let value_cell = BoundingBox {
+            left: left + 0.5,
+            bottom,
+            right: left + column - 0.5,
+            top: divider,
+        };
+        draw_fitted_text(
+            canvas,
+            value,
+            &value_cell,
+            &value_style,
+            FONT_SIZE_TINY,
+            font,
+        )?;
+    }
+    Ok(())
+}
+
+fn draw_immitance_panel(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    x: f32,
+    y: f32,
+    title: &str,
+    immittance: &EarImmittance,
+) -> Result<(), text::Error> {
+    draw_panel_title(canvas, font, title, x, y)?;
+    canvas.rect(x, y - 45.0, x + 90.0, y + 5.0, Paint::Stroke);
+    draw_text(
+        canvas,
+        font,
+        labels.tympanometry,
+        FONT_SIZE_SMALL,
+        x + 2.0,
+        y - 5.0,
+    )?;
+    if let Some(tymp) = &immittance.tympanogram {
+        // The values the Jerger type is classified on: measured on the
+        // samples when there are any
+        let measures = tymp.measures();
+        let mut lines = match measures {
+            Some(m) => vec![
+                format!("{}: {:.0} daPa", labels.pressure, m.peak_pressure),
+                format!("{}: {:.2} ml", labels.compliance, m.static_admittance),
+            ],
+            None => vec![labels.no_peak.to_string()],
+        };
+        lines.push(format!(
+            "{}: {:.2} ml",
+            labels.volume, tymp.ear_canal_volume
+        ));
+        if let Some(width) = measures.and_then(|m| m.width) {
+            lines.push(format!("{}: {:.0} daPa", labels.gradient, width));
+        }
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(
+                canvas,
+                font,
+                line.as_str(),
+                FONT_SIZE_SMALL,
+                x + 57.0,
+                y - 11.0 - i as f32 * 4.0,
+            )?;
+        }
+    }
+    draw_text(
+        canvas,
+        font,
+        labels.acoustic_reflex,
+        FONT_SIZE_SMALL,
+        x + 2.0,
+        y - 34.0,
+    )?;
+    for (i, reflex) in immittance.reflexes.iter().enumerate() {
+        let route = match reflex.route {
+            ReflexRoute::Ipsilateral => labels.ipsilateral,
+            ReflexRoute::Contralateral => labels.contralateral,
+        };
+        let level = match reflex.level {
+            Some(level) => level.to_string(),
+            None => labels.no_response.to_string(),
+        };
+        draw_text(
+            canvas,
+            font,
+            &format!("{} {} Hz: {}", route, reflex.frequency, level),
+            FONT_SIZE_SMALL,
+            x + 2.0 + (i % 4) as f32 * 22.0,
+            y - 38.0 - (i / 4) as f32 * 4.0,
+        )?;
+    }
+    Ok(())
+}
+
+/// Tympanogram on a grid filling `grid`: compliance against pressure over
+/// [`TYMPANOGRAM_PRESSURE`], as a smooth curve through the samples, over
+/// the shaded normal range. Its Jerger type goes right of the grid.
+fn draw_tympanogram(
+    canvas: &mut dyn Canvas,
+    font: &TextMeasurer,
+    labels: &Labels,
+    grid: &BoundingBox,
+    tympanogram: Option<&Tympanogram>,
+    color: SymbolColor,
+    norms: &TympanogramNorms,
+) -> Result<(), text::Error> {
+    // Admittance is classified as the peak's height above the positive
+    // tail, so the normal range is shaded above the tail too; it has no
+    // upper pressure bound
+    let baseline = tympanogram.map_or(0.0, Tympanogram::baseline);
+    let (left, bottom) = tympanogram_point(grid, norms.min_pressure, baseline + norms.admittance.0);
+    let (right, top) =
+        tympanogram_point(grid, TYMPANOGRAM_PRESSURE.1, baseline + norms.admittance.1);
+    canvas.save_graphics_state();
+    canvas.set_fill_color(SymbolColor::Gray(0.85));
+    canvas.rect(left, bottom, right, top, Paint::Fill);
+    canvas.restore_graphics_state();
+
+    draw_simple_grid(
+        canvas,
+        font,
+        grid.left,
+        grid.bottom,
+        grid.width(),
+        grid.height(),
+        6,
+        5,
+        "-400",
+        "200",
+        "0",
+        "2.5 ml",
+    )?;
+    draw_text_in_box(
+        canvas,
+        "daPa",
+        &line_box(grid.left, grid.right, grid.bottom - 2.5),
+        HAlign::Center,
+        VAlign::Baseline,
+        font,
+        FONT_SIZE_TINY,
+    )?;
+
+    if let Some(tympanogram) = tympanogram {
+        draw_tympanogram_curve(canvas, grid, tympanogram, color);
+        let jerger = tympanogram.jerger_type(norms);
+        draw_text(
+            canvas,
+            font,
+            &format!("{} {}", labels.tympanogram_type, jerger.name()),
+            FONT_SIZE_NORMAL,
+            grid.right + 4.0,
+            grid.bottom,
+        )?;
+    }
+    Ok(())
+}
+
+/// Page position of `pressure` and `compliance` on a tympanogram filling
+/// `grid`. Compliance is clamped to the grid.
+fn tympanogram_point(grid: &BoundingBox, pressure: f32, compliance: f32) -> (f32, f32) {
+    let (min_pressure, max_pressure) = TYMPANOGRAM_PRESSURE;
+    let x = (pressure - min_pressure) / (max_pressure - min_pressure);
+    let y = compliance.clamp(0.0, TYMPANOGRAM_MAX_COMPLIANCE) / TYMPANOGRAM_MAX_COMPLIANCE;
+    (
+        grid.left + x * grid.width(),
+        grid.bottom + y * grid.height(),
+    )
+}
+
+/// Smooth curve through the samples of `tympanogram` within
+/// [`TYMPANOGRAM_PRESSURE`], on a tympanogram filling `grid`.
+fn draw_tympanogram_curve(
+    canvas: &mut dyn Canvas,
+    grid: &BoundingBox,
+    tympanogram: &Tympanogram,
+    color: SymbolColor,
+) {
+    let (min_pressure, max_pressure) = TYMPANOGRAM_PRESSURE;
+    let points: Vec<(f32, f32)> = tympanogram
+        .samples
+        .iter()
+        .filter(|s| (min_pressure..=max_pressure).contains(&s.pressure))
+        .map(|s| tympanogram_point(grid, s.pressure, s.compliance))
+        .collect();
+    if points.len() < 2 {
+        return;
+    }
+    canvas.save_graphics_state();
+    canvas.set_outline_color(color);
+    canvas.set_outline_thickness(0.5);
+    canvas.draw_path(&Path::smooth_curve(&points), Paint::Stroke);
+    canvas.restore_graphics_state();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::{Command, PathOp};
+    use crate::labels;
+    use crate::model::{NoiseTest, PiPoint, SpeechInNoise, TympanogramSample, WordRecognition};
+    use owned_ttf_parser::Face;
+    use printpdf::lopdf;
+
+    const ROBOTO: &[u8] = include_bytes!("../vendor/printpdf/assets/fonts/RobotoMedium.ttf");
+    const JANE_DOE: &str = include_str!("../examples/jane_doe.json");
+
+    fn texts(canvas: &RecordingCanvas) -> Vec<&str> {
+        canvas
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A 125-8000 Hz grid ten millimeters per octave wide, from x = 20,
+    /// and one millimeter per dB from -10 dB HL at y = 200.
+    fn audiogram_grid() -> AudiogramGrid {
+        AudiogramGrid {
+            left: 20.0,
+            top: 200.0,
+            width: 60.0,
+            height: (MAX_LEVEL - MIN_LEVEL) as f32,
+            max_frequency: 8000,
+        }
+    }
+
+    fn threshold(frequency: u32, level: i32, masked: bool, no_response: bool) -> Threshold {
+        Threshold {
+            frequency,
+            level,
+            masked,
+            no_response,
+        }
+    }
+
+    /// Whether `canvas` holds the commands `draw_symbol` records for
+    /// `symbol` at `(x, y)`.
+    fn draws_symbol(
+        canvas: &RecordingCanvas,
+        symbol: Symbol,
+        (x, y): (f32, f32),
+        style: &SymbolStyle,
+    ) -> bool {
+        let mut expected = RecordingCanvas::default();
+        draw_symbol(&mut expected, symbol, (Mm(x), Mm(y)), style);
+        records(canvas, &expected)
+    }
+
+    /// Whether `canvas` holds every command of `expected`, in a row.
+    fn records(canvas: &RecordingCanvas, expected: &RecordingCanvas) -> bool {
+        canvas
+            .commands
+            .windows(expected.commands.len())
+            .any(|window| window == expected.commands.as_slice())
+    }
+
+    #[test]
+    fn symbols_follow_asha() {
+        use Symbol::*;
+        let expected = [
+            (Ear::Right, Conduction::Air, [Circle, Triangle]),
+            (Ear::Left, Conduction::Air, [X, Square]),
+            (Ear::Right, Conduction::Bone, [Less, LeftBracket]),
+            (Ear::Left, Conduction::Bone, [Greater, RightBracket]),
+        ];
+        for (ear, conduction, [unmasked, masked]) in expected {
+            assert_eq!(threshold_symbol(ear, conduction, false), unmasked);
+            assert_eq!(threshold_symbol(ear, conduction, true), masked);
+        }
+    }
+
+    #[test]
+    fn bone_symbols_sit_beside_the_frequency_line() {
+        let grid = audiogram_grid();
+        let size = pt_to_mm(THRESHOLD_SYMBOL_SIZE);
+        let style = SymbolStyle::new(THRESHOLD_SYMBOL_SIZE, SymbolColor::BLACK);
+        let thresholds = EarThresholds {
+            air: vec![
+                threshold(1000, 40, false, false),
+                threshold(2000, 50, true, false),
+            ],
+            bone: vec![
+                threshold(1000, 30, false, false),
+                threshold(2000, 45, true, false),
+            ],
+        };
+        let (x1000, x2000) = (50.0, 60.0);
+        let y = |level: i32| grid.y_for(level);
+        for (ear, side) in [(Ear::Right, -1.0), (Ear::Left, 1.0)] {
+            let mut canvas = RecordingCanvas::default();
+            draw_thresholds(&mut canvas, &grid, ear, &thresholds, SymbolColor::BLACK);
+            let drawn = [
+                (Conduction::Air, false, (x1000, y(40))),
+                (Conduction::Air, true, (x2000, y(50))),
+                (Conduction::Bone, false, (x1000 + side * size / 2.0, y(30))),
+                (Conduction::Bone, true, (x2000 + side * size / 2.0, y(45))),
+            ];
+            for (conduction, masked, at) in drawn {
+                let symbol = threshold_symbol(ear, conduction, masked);
+                assert!(
+                    draws_symbol(&canvas, symbol, at, &style),
+                    "{:?} {:?} not at {:?}",
+                    ear,
+                    symbol,
+                    at
+                );
+            }
+            // the air symbols are not also drawn on the bone offset
+            let offset = (x1000 + side * size / 2.0, y(40));
+            let air = threshold_symbol(ear, Conduction::Air, false);
+            assert!(!draws_symbol(&canvas, air, offset, &style));
+        }
+    }
+
+    #[test]
+    fn threshold_line_breaks_at_no_response() {
+        let grid = audiogram_grid();
+        let size = pt_to_mm(THRESHOLD_SYMBOL_SIZE);
+        let thresholds = EarThresholds {
+            air: vec![
+                threshold(500, 40, false, false),
+                threshold(1000, 50, false, false),
+                threshold(2000, 120, false, true),
+                threshold(4000, 60, false, false),
+                threshold(8000, 70, false, false),
+            ],
+            bone: Vec::new(),
+        };
+        let mut canvas = RecordingCanvas::default();
+        draw_thresholds(
+            &mut canvas,
+            &grid,
+            Ear::Right,
+            &thresholds,
+            SymbolColor::BLACK,
+        );
+
+        let points: Vec<_> = thresholds
+            .air
+            .iter()
+            .map(|t| grid.point_for(t).unwrap())
+            .collect();
+        let links: Vec<bool> = points
+            .windows(2)
+            .map(|pair| {
+                let mut link = RecordingCanvas::default();
+                draw_symbol_link(&mut link, pair[0], pair[1], size);
+                assert_eq!(link.commands.len(), 1);
+                canvas.commands.contains(&link.commands[0])
+            })
+            .collect();
+        assert_eq!(links, [true, false, false, true]);
+
+        // the no-response symbol still sits at the maximum level, with the
+        // arrow hanging down-left from it
+        let style = SymbolStyle::new(THRESHOLD_SYMBOL_SIZE, SymbolColor::BLACK);
+        assert!(draws_symbol(&canvas, Symbol::Circle, points[2], &style));
+        let mut arrow = RecordingCanvas::default();
+        draw_no_response_arrow(&mut arrow, Ear::Right, points[2], SymbolColor::BLACK);
+        assert!(records(&canvas, &arrow));
+        let (x, y) = points[2];
+        let arrow_points: Vec<_> = arrow
+            .paths()
+            .flat_map(|(path, _)| &path.ops)
+            .filter_map(|op| match *op {
+                PathOp::MoveTo(x, y) | PathOp::LineTo(x, y) => Some((x, y)),
+                _ => None,
+            })
+            .collect();
+        assert!(!arrow_points.is_empty());
+        for (ax, ay) in arrow_points {
+            assert!(
+                ax < x && ay < y,
+                "arrow point {:?} not below left of {:?}",
+                (ax, ay),
+                (x, y)
+            );
+        }
+    }
+
+    #[test]
+    fn legend_lists_only_the_symbols_used() {
+        let labels = &labels::EN;
+        let air_only = EarThresholds {
+            air: vec![threshold(1000, 40, false, false)],
+            bone: Vec::new(),
+        };
+        assert_eq!(
+            legend_rows(labels, Ear::Right, &air_only),
+            [
+                LegendRow::Heading(labels.air_conduction),
+                LegendRow::Symbol(Symbol::Circle, labels.unmasked),
+            ]
+        );
+
+        let mixed = EarThresholds {
+            air: vec![threshold(1000, 40, true, false)],
+            bone: vec![
+                threshold(1000, 30, false, false),
+                threshold(4000, 70, true, true),
+            ],
+        };
+        assert_eq!(
+            legend_rows(labels, Ear::Left, &mixed),
+            [
+                LegendRow::Heading(labels.air_conduction),
+                LegendRow::Symbol(Symbol::Square, labels.masked),
+                LegendRow::Heading(labels.bone_conduction),
+                LegendRow::Symbol(Symbol::Greater, labels.unmasked),
+                LegendRow::Symbol(Symbol::RightBracket, labels.masked),
+                LegendRow::NoResponse,
+            ]
+        );
+
+        assert!(legend_rows(labels, Ear::Left, &EarThresholds::default()).is_empty());
+        let mut canvas = RecordingCanvas::default();
+        let font = TextMeasurer::helvetica();
+        draw_legend(
+            &mut canvas,
+            &font,
+            labels,
+            &audiogram_grid(),
+            LegendPosition::UpperLeft,
+            Ear::Left,
+            &EarThresholds::default(),
+            SymbolColor::BLACK,
+        )
+        .unwrap();
+        assert!(canvas.commands.is_empty());
+    }
+
+    #[test]
+    fn legend_goes_in_the_chosen_corner() {
+        let grid = audiogram_grid();
+        let font = TextMeasurer::helvetica();
+        let thresholds = EarThresholds {
+            air: vec![threshold(1000, 40, false, false)],
+            bone: Vec::new(),
+        };
+        // the box of two rows, 1.5 mm inside the 20..80 x 70..200 grid
+        let (width, height) = (27.0, 2.0 * 3.5 + 1.0);
+        let corners = [
+            (LegendPosition::UpperLeft, (21.5, 198.5 - height)),
+            (LegendPosition::UpperRight, (78.5 - width, 198.5 - height)),
+            (LegendPosition::LowerLeft, (21.5, 71.5)),
+            (LegendPosition::LowerRight, (78.5 - width, 71.5)),
+        ];
+        for (position, (left, bottom)) in corners {
+            let mut canvas = RecordingCanvas::default();
+            draw_legend(
+                &mut canvas,
+                &font,
+                &labels::EN,
+                &grid,
+                position,
+                Ear::Right,
+                &thresholds,
+                SymbolColor::BLACK,
+            )
+            .unwrap();
+            let (background, paint) = canvas.paths().next().unwrap();
+            assert_eq!(paint, Paint::FillStroke);
+            let (right, top) = (left + width, bottom + height);
+            let expected =
+                Path::polygon(&[(left, bottom), (right, bottom), (right, top), (left, top)]);
+            assert_eq!(background.ops.len(), expected.ops.len());
+            for (op, expected) in background.ops.iter().zip(&expected.ops) {
+                if let (
+                    PathOp::MoveTo(x, y) | PathOp::LineTo(x, y),
+                    PathOp::MoveTo(ex, ey) | PathOp::LineTo(ex, ey),
+                ) = (*op, *expected)
+                {
+                    assert!(
+                        (x - ex).abs() < 1e-4 && (y - ey).abs() < 1e-4,
+                        "{:?}: {:?}",
+                        position,
+                        background
+                    );
+                }
+            }
+        }
+
+        let mut hidden = RecordingCanvas::default();
+        draw_legend(
+            &mut hidden,
+            &font,
+            &labels::EN,
+            &grid,
+            LegendPosition::Hidden,
+            Ear::Right,
+            &thresholds,
+            SymbolColor::BLACK,
+        )
+        .unwrap();
+        assert!(hidden.commands.is_empty());
+    }
+
+    #[test]
+    fn octaves_are_evenly_spaced() {
+        let grid = audiogram_grid();
+        for (i, frequency) in OCTAVE_FREQUENCIES.into_iter().enumerate() {
+            let x = grid.x_for(frequency).unwrap();
+            assert!(
+                (x - (20.0 + 10.0 * i as f32)).abs() < 1e-4,
+                "{} at {}",
+                frequency,
+                x
+            );
+        }
+        assert_eq!(grid.x_for(100), None);
+        assert_eq!(grid.x_for(10000), None);
+    }
+
+    #[test]
+    fn inter_octave_frequencies_fall_between_their_octaves() {
+        let grid = audiogram_grid();
+        // 1.5 times an octave is log2(1.5) of the way to the next one,
+        // a little past halfway, and the same for every inter-octave point
+        let fraction = 1.5f32.log2();
+        for frequency in INTER_OCTAVE_FREQUENCIES {
+            let below = grid.x_for(frequency * 2 / 3).unwrap();
+            let above = grid.x_for(frequency * 4 / 3).unwrap();
+            let x = grid.x_for(frequency).unwrap();
+            assert!((x - (below + fraction * (above - below))).abs() < 1e-4);
+            assert!(((x - below) / (above - below) - 0.5).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn extended_high_frequencies_widen_the_axis() {
+        let grid = AudiogramGrid {
+            max_frequency: 16000,
+            ..audiogram_grid()
+        };
+        // seven octaves now share the width
+        let octave = 60.0 / 7.0;
+        let x = |frequency| grid.x_for(frequency).unwrap();
+        assert!((x(125) - 20.0).abs() < 1e-4);
+        assert!((x(8000) - (20.0 + 6.0 * octave)).abs() < 1e-4);
+        assert!((x(16000) - 80.0).abs() < 1e-4);
+        assert!(x(8000) < x(10000) && x(10000) < x(12500) && x(12500) < x(16000));
+        assert!((x(10000) - x(8000) - 1.25f32.log2() * octave).abs() < 1e-4);
+        assert_eq!(grid.x_for(20000), None);
+    }
+
+    #[test]
+    fn speech_table_prints_every_result() {
+        let font = TextMeasurer::helvetica();
+        let speech = EarSpeech {
+            srt: Some(40),
+            sdt: Some(35),
+            mcl: Some(75),
+            ucl: Some(105),
+            word_recognition: Some(WordRecognition {
+                score: 88.0,
+                level: 80,
+                list: Some("1A".to_string()),
+                masking: Some(60),
+                modality: Some(Modality::Recorded),
+            }),
+            performance_intensity: Vec::new(),
+            in_noise: Some(SpeechInNoise {
+                test: NoiseTest::QuickSin,
+                snr50: Some(9.5),
+                snr_loss: Some(7.5),
+            }),
+        };
+        let mut canvas = RecordingCanvas::default();
+        draw_speech_table(&mut canvas, &font, &labels::EN, 10.0, 140.0, "", &speech).unwrap();
+        let drawn = texts(&canvas);
+        let values = [
+            "40", "35", "75", "105", "88", "80", "1A", "60", "Recorded", "QuickSIN", "9.5", "7.5",
+            "Moderate",
+        ];
+        for value in values {
+            assert!(drawn.contains(&value), "{} missing from {:?}", value, drawn);
+        }
+
+        // nothing tested: the same headings over empty cells
+        let mut empty = RecordingCanvas::default();
+        let none = EarSpeech::default();
+        draw_speech_table(&mut empty, &font, &labels::EN, 10.0, 140.0, "", &none).unwrap();
+        assert_eq!(texts(&empty).len(), drawn.len() - values.len());
+    }
+
+    #[test]
+    fn pi_chart_prints_the_rollover_index() {
+        let font = TextMeasurer::helvetica();
+        let grid = BoundingBox {
+            left: 75.0,
+            bottom: 102.0,
+            right: 98.0,
+            top: 130.0,
+        };
+        let levels = [(50, 60.0), (60, 80.0), (80, 72.0), (90, 60.0)];
+        let speech = EarSpeech {
+            performance_intensity: levels
+                .iter()
+                .map(|&(level, score)| PiPoint { level, score })
+                .collect(),
+            ..EarSpeech::default()
+        };
+        let mut canvas = RecordingCanvas::default();
+        let color = SymbolColor::BLACK;
+        draw_pi_chart(
+            &mut canvas,
+            &font,
+            &labels::EN,
+            &grid,
+            Ear::Right,
+            &speech,
+            color,
+        )
+        .unwrap();
+        assert!(texts(&canvas).contains(&"RI: 0.25"), "{:?}", texts(&canvas));
+
+        // without a level above the best score there is no index to print
+        let mut canvas = RecordingCanvas::default();
+        let speech = EarSpeech::default();
+        draw_pi_chart(
+            &mut canvas,
+            &font,
+            &labels::EN,
+            &grid,
+            Ear::Left,
+            &speech,
+            color,
+        )
+        .unwrap();
+        assert!(!texts(&canvas).iter().any(|t| t.starts_with("RI")));
+    }
+
+    #[test]
+    fn tympanogram_is_a_curve_through_its_samples() {
+        let font = TextMeasurer::helvetica();
+        let grid = BoundingBox {
+            left: 20.0,
+            bottom: 50.0,
+            right: 80.0,
+            top: 70.0,
+        };
+        let samples = [
+            (-600.0, 0.1),
+            (-200.0, 0.2),
+            (-20.0, 0.7),
+            (100.0, 0.3),
+            (200.0, 0.2),
+        ];
+        let tympanogram = Tympanogram {
+            peak_pressure: -20.0,
+            peak_compliance: 0.7,
+            ear_canal_volume: 1.2,
+            gradient: Some(90.0),
+            samples: samples
+                .iter()
+                .map(|&(pressure, compliance)| TympanogramSample {
+                    pressure,
+                    compliance,
+                })
+                .collect(),
+        };
+        let mut canvas = RecordingCanvas::default();
+        let norms = TympanogramNorms::default();
+        draw_tympanogram(
+            &mut canvas,
+            &font,
+            &labels::EN,
+            &grid,
+            Some(&tympanogram),
+            SymbolColor::RED,
+            &norms,
+        )
+        .unwrap();
+
+        // the normal range is shaded: from -100 daPa, 30 mm along the
+        // 600 daPa wide grid, to its right edge, and 0.3..1.5 ml above the
+        // 0.2 ml tail is 4..13.6 mm up its 2.5 ml
+        let close =
+            |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4;
+        let (shade, _) = canvas
+            .paths()
+            .find(|(_, paint)| *paint == Paint::Fill)
+            .unwrap();
+        assert!(matches!(shade.ops[0], PathOp::MoveTo(x, y) if close((x, y), (50.0, 54.0))));
+        assert!(matches!(shade.ops[2], PathOp::LineTo(x, y) if close((x, y), (80.0, 63.6))));
+
+        // -600 daPa is off the grid; the other samples are joined smoothly
+        let (curve, _) = canvas.paths().last().unwrap();
+        assert_eq!(curve.ops.len(), 4);
+        assert!(matches!(curve.ops[0], PathOp::MoveTo(x, y) if close((x, y), (40.0, 51.6))));
+        assert!(matches!(curve.ops[3], PathOp::CurveTo(_, _, end) if close(end, (80.0, 51.6))));
+        assert!(texts(&canvas).contains(&"-400"));
+        assert!(texts(&canvas).contains(&"Type A"));
+    }
+
+    #[test]
+    fn immittance_panel_prints_the_measured_values() {
+        let font = TextMeasurer::helvetica();
+        let sampled = |samples: &[(f32, f32)]| Tympanogram {
+            peak_pressure: -20.0,
+            peak_compliance: 0.7,
+            ear_canal_volume: 1.2,
+            gradient: Some(90.0),
+            samples: samples
+                .iter()
+                .map(|&(pressure, compliance)| TympanogramSample {
+                    pressure,
+                    compliance,
+                })
+                .collect(),
+        };
+        let panel = |tympanogram: Tympanogram| {
+            let immittance = EarImmittance {
+                tympanogram: Some(tympanogram),
+                reflexes: Vec::new(),
+            };
+            let mut canvas = RecordingCanvas::default();
+            draw_immitance_panel(&mut canvas, &font, &labels::EN, 10.0, 80.0, "", &immittance)
+                .unwrap();
+            canvas
+        };
+
+        // without samples, the summary values
+        let canvas = panel(sampled(&[]));
+        let drawn = texts(&canvas);
+        for line in [
+            "Pressure: -20 daPa",
+            "Compliance: 0.70 ml",
+            "Volume: 1.20 ml",
+            "Gradient: 90 daPa",
+        ] {
+            assert!(drawn.contains(&line), "{} missing from {:?}", line, drawn);
+        }
+
+        // with samples, what they measure: the peak 0.5 ml above the tail,
+        // 50 daPa wide at 0.45 ml
+        let samples = [
+            (-200.0, 0.2),
+            (-75.0, 0.2),
+            (-25.0, 0.7),
+            (25.0, 0.2),
+            (200.0, 0.2),
+        ];
+        let canvas = panel(sampled(&samples));
+        let drawn = texts(&canvas);
+        for line in [
+            "Pressure: -25 daPa",
+            "Compliance: 0.50 ml",
+            "Volume: 1.20 ml",
+            "Gradient: 50 daPa",
+        ] {
+            assert!(drawn.contains(&line), "{} missing from {:?}", line, drawn);
+        }
+
+        // a flat curve has no peak to measure
+        let canvas = panel(sampled(&[(-200.0, 0.3), (0.0, 0.3), (200.0, 0.3)]));
+        let drawn = texts(&canvas);
+        assert!(drawn.contains(&"No peak"));
+        assert!(!drawn.iter().any(|text| text.starts_with("Pressure")));
+    }
+
+    #[test]
+    fn long_clinician_names_stay_out_of_the_top_margin() {
+        let mut eval = crate::input::from_json(JANE_DOE).unwrap();
+        eval.clinician.name = "Marie-Christine Bolduc-Desrosiers-Villeneuve, \
+            audiologiste, Clinique d'audiologie du Grand Montréal"
+            .to_string();
+        let mut canvas = RecordingCanvas::default();
+        let font = TextMeasurer::helvetica();
+        draw_header(&mut canvas, &font, &labels::EN, &eval, ColorScheme::Asha).unwrap();
+
+        let name: Vec<_> = canvas
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::Text {
+                    text, font_size, y, ..
+                } if text.starts_with("MARIE") => Some((text, *font_size, *y)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(name.len(), 1, "{:?}", name);
+        let (text, font_size, y) = name[0];
+        assert!(text.ends_with('\u{2026}'), "{:?}", text);
+        assert_eq!(y, 280.0);
+        let ascent = font.measure("", font_size).unwrap().ascent;
+        assert!(y + ascent <= PAGE_HEIGHT - MARGIN);
+    }
+
+    #[test]
+    fn header_shows_both_tympanograms() {
+        let eval = crate::input::from_json(JANE_DOE).unwrap();
+        let mut canvas = RecordingCanvas::default();
+        let font = TextMeasurer::helvetica();
+        draw_header(&mut canvas, &font, &labels::EN, &eval, ColorScheme::Asha).unwrap();
+
+        let mut color = SymbolColor::BLACK;
+        let mut curves = Vec::new();
+        for command in &canvas.commands {
+            match command {
+                Command::SetOutlineColor(c) => color = *c,
+                Command::DrawPath(path, _)
+                    if matches!(path.ops.last(), Some(PathOp::CurveTo(..))) =>
+                {
+                    curves.push((color, path));
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(curves.len(), 2);
+        assert_eq!(curves[0].0, SymbolColor::RED);
+        assert_eq!(curves[1].0, SymbolColor::BLUE);
+        for (_, curve) in curves {
+            // -400 and 200 daPa are the left and right edges of the grid
+            assert!(matches!(curve.ops[0], PathOp::MoveTo(x, _) if (x - 160.0).abs() < 1e-4));
+            assert!(
+                matches!(curve.ops.last(), Some(PathOp::CurveTo(_, _, (x, _))) if (x - 200.0).abs() < 1e-4)
+            );
+        }
+    }
+
+    #[test]
+    fn subset_font_still_draws_every_character() {
+        let eval = crate::input::from_json(JANE_DOE).unwrap();
+        let mut options = ReportOptions::default();
+        let id = options
+            .fonts
+            .add_font_as("Roboto", Weight::MEDIUM, Style::Normal, ROBOTO.to_vec())
+            .unwrap();
+        options.text_font = FontSelection::new("Roboto", Weight::MEDIUM, Style::Normal);
+        options.title_font = options.text_font.clone();
+
+        let fonts = Fonts::new(&options).unwrap();
+        let mut recording = RecordingCanvas::default();
+        draw_page(&mut recording, &fonts, &eval, &options).unwrap();
+        let used = recording.characters().remove(&id).unwrap();
+
+        let pdf = render(&eval, &options).unwrap().save_to_bytes().unwrap();
+        let doc = lopdf::Document::load_mem(&pdf).unwrap();
+        let embedded: Vec<Vec<u8>> = doc
+            .objects
+            .values()
+            .filter_map(|object| object.as_dict().ok()?.get(b"FontFile2").ok())
+            .map(|file| {
+                let stream = doc.get_object(file.as_reference().unwrap()).unwrap();
+                let stream = stream.as_stream().unwrap();
+                stream
+                    .decompressed_content()
+                    .unwrap_or_else(|_| stream.content.clone())
+            })
+            .collect();
+        assert_eq!(embedded.len(), 1);
+        assert!(pdf.len() < ROBOTO.len() / 2, "{} bytes", pdf.len());
+
+        let full = Face::parse(ROBOTO, 0).unwrap();
+        let font = Face::parse(&embedded[0], 0).unwrap();
+        for c in used {
+            let gid = full.glyph_index(c).unwrap();
+            assert_eq!(font.glyph_index(c), Some(gid), "{:?}", c);
+            assert_eq!(
+                font.glyph_bounding_box(gid),
+                full.glyph_bounding_box(gid),
+                "{:?}",
+                c
+            );
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use printpdf::*;
-use printpdf::path::{PaintMode, WindingOrder};
 
-#[derive(Copy, Clone, PartialEq)]
+use crate::canvas::{Canvas, Paint, Path};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Symbol {
     Square,
     SquareFilled,
@@ -35,303 +36,694 @@ pub enum Symbol {
     VTFilled,
 }
 
-#[derive(Copy, Clone)]
+/// Ink color for symbols and curves. Components run from 0.0 to 1.0.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum SymbolColor {
-    Red,
-    Blue,
+    Rgb(f32, f32, f32),
+    Cmyk(f32, f32, f32, f32),
+    Gray(f32),
 }
 
 impl SymbolColor {
-    fn to_color(&self) -> Color {
+    pub const RED: SymbolColor = SymbolColor::Rgb(1.0, 0.0, 0.0);
+    pub const BLUE: SymbolColor = SymbolColor::Rgb(0.0, 0.0, 1.0);
+    pub const BLACK: SymbolColor = SymbolColor::Gray(0.0);
+
+    /// Color from 8-bit sRGB components.
+    pub fn rgb8(r: u8, g: u8, b: u8) -> Self {
+        SymbolColor::Rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+    }
+
+    pub fn to_color(self) -> Color {
         match self {
-            SymbolColor::Red => Color::Rgb(Rgb::new(1.0, 0.0, 0.0, None)),
-            SymbolColor::Blue => Color::Rgb(Rgb::new(0.0, 0.0, 1.0, None)),
+            SymbolColor::Rgb(r, g, b) => Color::Rgb(Rgb::new(r, g, b, None)),
+            SymbolColor::Cmyk(c, m, y, k) => Color::Cmyk(Cmyk::new(c, m, y, k, None)),
+            SymbolColor::Gray(g) => Color::Greyscale(Greyscale::new(g, None)),
         }
     }
 }
 
-pub fn draw_symbol(
-    layer: &PdfLayerReference,
-    font: &IndirectFontRef,
-    symbol: Symbol,
-    pos: (Mm, Mm),
-    size: f32,
-    color: SymbolColor,
-) {
-    use Symbol::*;
+/// Where the position passed to [`draw_symbol`] sits on the symbol box.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    #[default]
+    Center,
+    BottomLeft,
+}
 
-    match symbol {
-        Square | SquareFilled => {
-            draw_square(layer, pos, size, symbol == SquareFilled, color);
-        }
-        Triangle | TriangleFilled => {
-            draw_triangle(layer, pos, size, symbol == TriangleFilled, color);
-        }
-        Circle | CircleFilled => {
-            draw_circle(layer, pos, size, symbol == CircleFilled, color);
-        }
-        X | XFilled => {
-            draw_x(layer, pos, size, symbol == XFilled, color);
-        }
-        Greater | GreaterFilled => {
-            draw_angle(layer, pos, size, false, symbol == GreaterFilled, color);
-        }
-        Less | LessFilled => {
-            draw_angle(layer, pos, size, true, symbol == LessFilled, color);
-        }
-        LeftBracket | LeftBracketFilled => {
-            draw_bracket(layer, pos, size, true, symbol == LeftBracketFilled, color);
+/// How a symbol is drawn.
+#[derive(Copy, Clone, Debug)]
+pub struct SymbolStyle {
+    /// Side of the symbol's square box, in points.
+    pub size: f32,
+    /// Outline width in points. Outline-only symbols (X, brackets, letters)
+    /// show their filled variant at twice this width.
+    pub stroke_width: f32,
+    /// Draw the filled form even for the outline variants.
+    pub filled: bool,
+    pub color: SymbolColor,
+    pub anchor: Anchor,
+}
+
+impl SymbolStyle {
+    /// Centered outline symbol of `size` points, with a 0.5 pt stroke.
+    pub fn new(size: f32, color: SymbolColor) -> Self {
+        SymbolStyle {
+            size,
+            stroke_width: 0.5,
+            filled: false,
+            color,
+            anchor: Anchor::Center,
         }
-        RightBracket | RightBracketFilled => {
-            draw_bracket(layer, pos, size, false, symbol == RightBracketFilled, color);
+    }
+}
+
+/// Axis-aligned box, in millimetres from the page's lower-left corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32,
+}
+
+impl BoundingBox {
+    pub fn width(&self) -> f32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> f32 {
+        self.top - self.bottom
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (
+            (self.left + self.right) / 2.0,
+            (self.bottom + self.top) / 2.0,
+        )
+    }
+}
+
+impl Symbol {
+    /// Whether this is one of the `*Filled` variants.
+    pub fn is_filled(self) -> bool {
+        use Symbol::*;
+        matches!(
+            self,
+            SquareFilled
+                | TriangleFilled
+                | CircleFilled
+                | SFilled
+                | UFilled
+                | XFilled
+                | AFilled
+                | GreaterFilled
+                | LessFilled
+                | LeftBracketFilled
+                | RightBracketFilled
+                | StarFilled
+                | ArrowDownRightFilled
+                | ArrowDownLeftFilled
+                | VTFilled
+        )
+    }
+
+    /// Symbols drawn as bare strokes, which show their filled form with a
+    /// heavier stroke instead of a fill.
+    fn is_outline_only(self) -> bool {
+        use Symbol::*;
+        matches!(
+            self,
+            X | XFilled
+                | Greater
+                | GreaterFilled
+                | Less
+                | LessFilled
+                | LeftBracket
+                | LeftBracketFilled
+                | RightBracket
+                | RightBracketFilled
+                | S
+                | SFilled
+                | U
+                | UFilled
+                | A
+                | AFilled
+                | VT
+                | VTFilled
+        )
+    }
+
+    /// Extent of the drawn geometry in unit box coordinates, as
+    /// `(left, bottom, right, top)`; see [`Frame`].
+    fn extent(self) -> (f32, f32, f32, f32) {
+        use Symbol::*;
+        match self {
+            S | SFilled | U | UFilled => (-0.3, -0.5, 0.3, 0.5),
+            A | AFilled => (-0.38, -0.5, 0.38, 0.5),
+            LeftBracket | LeftBracketFilled => (-0.5, -0.5, 0.1, 0.5),
+            RightBracket | RightBracketFilled => (-0.1, -0.5, 0.5, 0.5),
+            Star | StarFilled => (-STAR_HALF_WIDTH, STAR_BOTTOM, STAR_HALF_WIDTH, 0.5),
+            _ => (-0.5, -0.5, 0.5, 0.5),
         }
-        _ => {
-            let text = match symbol {
-                Square => unreachable!(),
-                SquareFilled => unreachable!(),
-                Triangle => unreachable!(),
-                TriangleFilled => unreachable!(),
-                Circle => unreachable!(),
-                CircleFilled => unreachable!(),
-                X | XFilled => unreachable!(),
-                S | SFilled => "S",
-                U | UFilled => "U",
-                A | AFilled => "A",
-                Greater | GreaterFilled => unreachable!(),
-                Less | LessFilled => unreachable!(),
-                LeftBracket | LeftBracketFilled => unreachable!(),
-                RightBracket | RightBracketFilled => unreachable!(),
-                Star => "\u{2606}", // ☆
-                StarFilled => "\u{2605}", // ★
-                ArrowDownRight | ArrowDownRightFilled => "\u{2198}",
-                ArrowDownLeft | ArrowDownLeftFilled => "\u{2199}",
-                VT | VTFilled => "VT",
-            };
-
-            let c = color.to_color();
-            layer.set_fill_color(c.clone());
-            layer.set_outline_color(c);
-            layer.use_text(text, size, pos.0, pos.1, font);
+    }
+
+    /// Box covering everything `draw_symbol` paints for this symbol at
+    /// `pos`, strokes included.
+    pub fn bounding_box(self, pos: (Mm, Mm), style: &SymbolStyle) -> BoundingBox {
+        let f = Frame::new(pos, style);
+        let (l, b, r, t) = self.extent();
+        let thick = (self.is_filled() || style.filled) && self.is_outline_only();
+        let pad = pt_to_mm(f.stroke_width(thick)) / 2.0;
+        BoundingBox {
+            left: f.cx + l * f.d - pad,
+            bottom: f.cy + b * f.d - pad,
+            right: f.cx + r * f.d + pad,
+            top: f.cy + t * f.d + pad,
         }
     }
 }
 
-fn pt_to_mm(pt: f32) -> f32 {
+/// Draw `symbol` at `pos`, placed according to `style.anchor`. The
+/// canvas's graphics state is left as it was.
+pub fn draw_symbol(canvas: &mut dyn Canvas, symbol: Symbol, pos: (Mm, Mm), style: &SymbolStyle) {
+    use Symbol::*;
+
+    let f = Frame::new(pos, style);
+    let filled = symbol.is_filled() || style.filled;
+
+    // Symbols set their own colors and line width; keep them off the page
+    canvas.save_graphics_state();
+    match symbol {
+        Square | SquareFilled => draw_square(canvas, &f, filled),
+        Triangle | TriangleFilled => draw_triangle(canvas, &f, filled),
+        Circle | CircleFilled => draw_circle(canvas, &f, filled),
+        X | XFilled => draw_x(canvas, &f, filled),
+        Greater | GreaterFilled => draw_angle(canvas, &f, false, filled),
+        Less | LessFilled => draw_angle(canvas, &f, true, filled),
+        LeftBracket | LeftBracketFilled => draw_bracket(canvas, &f, true, filled),
+        RightBracket | RightBracketFilled => draw_bracket(canvas, &f, false, filled),
+        Star | StarFilled => draw_star(canvas, &f, filled),
+        ArrowDownRight | ArrowDownRightFilled => draw_arrow(canvas, &f, false, filled),
+        ArrowDownLeft | ArrowDownLeftFilled => draw_arrow(canvas, &f, true, filled),
+        S | SFilled => draw_letter(canvas, &f, &LETTER_S, filled),
+        U | UFilled => draw_letter(canvas, &f, &LETTER_U, filled),
+        A | AFilled => draw_letter(canvas, &f, &LETTER_A, filled),
+        VT | VTFilled => draw_letter(canvas, &f, &LETTER_VT, filled),
+    }
+    canvas.restore_graphics_state();
+}
+
+pub fn pt_to_mm(pt: f32) -> f32 {
     pt * 25.4 / 72.0
 }
 
-fn draw_square(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    size_pt: f32,
-    filled: bool,
+// Star points alternate between these radii, starting at the top
+const STAR_OUTER: f32 = 0.5;
+const STAR_INNER: f32 = 0.2;
+// sin(36) and cos(18) of the outer radius: the lowest points and the widest
+const STAR_BOTTOM: f32 = -0.809_017 * STAR_OUTER;
+const STAR_HALF_WIDTH: f32 = 0.951_056_5 * STAR_OUTER;
+
+// Bracket arms reach this fraction of the box width in from its outer side
+const BRACKET_ARM_REACH: f32 = 0.6;
+
+/// A symbol's box: unit coordinates run from -0.5 to 0.5 on both axes,
+/// with the origin at the symbol's center.
+struct Frame {
+    cx: f32,
+    cy: f32,
+    d: f32,
+    stroke_width: f32,
     color: SymbolColor,
-) {
-    use printpdf::{Point, Polygon};
-    let side = pt_to_mm(size_pt);
-    let (x0, y0) = (origin.0 .0, origin.1 .0);
-    let pts = vec![
-        (Point::new(Mm(x0), Mm(y0)), false),
-        (Point::new(Mm(x0 + side), Mm(y0)), false),
-        (Point::new(Mm(x0 + side), Mm(y0 + side)), false),
-        (Point::new(Mm(x0), Mm(y0 + side)), false),
-    ];
-
-    let poly = Polygon {
-        rings: vec![pts],
-        mode: if filled { PaintMode::FillStroke } else { PaintMode::Stroke },
-        winding_order: WindingOrder::NonZero,
-    };
+}
+
+impl Frame {
+    fn new(pos: (Mm, Mm), style: &SymbolStyle) -> Self {
+        let d = pt_to_mm(style.size);
+        let (cx, cy) = match style.anchor {
+            Anchor::Center => (pos.0 .0, pos.1 .0),
+            Anchor::BottomLeft => (pos.0 .0 + d / 2.0, pos.1 .0 + d / 2.0),
+        };
+        Frame {
+            cx,
+            cy,
+            d,
+            stroke_width: style.stroke_width,
+            color: style.color,
+        }
+    }
+
+    /// Page millimetres of a unit-coordinate point.
+    fn pt(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (self.cx + x * self.d, self.cy + y * self.d)
+    }
+
+    /// Straight segments through unit-coordinate points.
+    fn polyline(&self, pts: &[(f32, f32)]) -> Path {
+        let pts: Vec<_> = pts.iter().map(|&p| self.pt(p)).collect();
+        Path::polyline(&pts)
+    }
+
+    /// Bezier chain `start, c1, c2, end, c1, c2, end, ...` in unit
+    /// coordinates.
+    fn curves(&self, pts: &[(f32, f32)]) -> Path {
+        let (x, y) = self.pt(pts[0]);
+        pts[1..]
+            .chunks_exact(3)
+            .fold(Path::new().move_to(x, y), |path, c| {
+                path.curve_to(self.pt(c[0]), self.pt(c[1]), self.pt(c[2]))
+            })
+    }
+
+    fn stroke_width(&self, thick: bool) -> f32 {
+        if thick {
+            self.stroke_width * 2.0
+        } else {
+            self.stroke_width
+        }
+    }
 
-    let c = color.to_color();
-    layer.set_fill_color(c.clone());
-    layer.set_outline_color(c);
-    layer.add_polygon(poly);
+    /// Set every piece of graphics state the symbol depends on, so it looks
+    /// the same whatever was drawn before it.
+    fn set_style(&self, canvas: &mut dyn Canvas, thick: bool) {
+        canvas.set_fill_color(self.color);
+        canvas.set_outline_color(self.color);
+        canvas.set_outline_thickness(self.stroke_width(thick));
+        canvas.set_line_dash(None);
+    }
 }
 
-fn draw_triangle(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    size_pt: f32,
-    filled: bool,
-    color: SymbolColor,
-) {
-    use printpdf::{Point, Polygon};
-    let side = pt_to_mm(size_pt);
-    let (x0, y0) = (origin.0 .0, origin.1 .0);
-    let pts = vec![
-        (Point::new(Mm(x0), Mm(y0)), false),
-        (Point::new(Mm(x0 + side / 2.0), Mm(y0 + side)), false),
-        (Point::new(Mm(x0 + side), Mm(y0)), false),
-    ];
-
-    let poly = Polygon {
-        rings: vec![pts],
-        mode: if filled { PaintMode::FillStroke } else { PaintMode::Stroke },
-        winding_order: WindingOrder::NonZero,
+/// One stroke of a vector letter: straight segments, or a cubic bezier
+/// chain `start, c1, c2, end, c1, c2, end, ...`.
+enum Stroke {
+    Lines(&'static [(f32, f32)]),
+    Curves(&'static [(f32, f32)]),
+}
+
+const LETTER_S: [Stroke; 1] = [Stroke::Curves(&[
+    (0.3, 0.35),
+    (0.2, 0.5),
+    (-0.3, 0.5),
+    (-0.3, 0.22),
+    (-0.3, 0.0),
+    (0.3, 0.05),
+    (0.3, -0.22),
+    (0.3, -0.5),
+    (-0.22, -0.5),
+    (-0.32, -0.32),
+])];
+
+// One continuous stroke; the straight sides are curves with their
+// control points on the ends
+const LETTER_U: [Stroke; 1] = [Stroke::Curves(&[
+    (-0.3, 0.5),
+    (-0.3, 0.5),
+    (-0.3, -0.2),
+    (-0.3, -0.2),
+    (-0.3, -0.5),
+    (0.3, -0.5),
+    (0.3, -0.2),
+    (0.3, -0.2),
+    (0.3, 0.5),
+    (0.3, 0.5),
+])];
+
+const LETTER_A: [Stroke; 2] = [
+    Stroke::Lines(&[(-0.38, -0.5), (0.0, 0.5), (0.38, -0.5)]),
+    Stroke::Lines(&[(-0.23, -0.1), (0.23, -0.1)]),
+];
+
+const LETTER_VT: [Stroke; 3] = [
+    Stroke::Lines(&[(-0.5, 0.5), (-0.25, -0.5), (0.0, 0.5)]),
+    Stroke::Lines(&[(0.05, 0.5), (0.5, 0.5)]),
+    Stroke::Lines(&[(0.275, 0.5), (0.275, -0.5)]),
+];
+
+fn shape(canvas: &mut dyn Canvas, path: Path, filled: bool) {
+    let paint = if filled {
+        Paint::FillStroke
+    } else {
+        Paint::Stroke
     };
+    canvas.draw_path(&path.close(), paint);
+}
 
-    let c = color.to_color();
-    layer.set_fill_color(c.clone());
-    layer.set_outline_color(c);
-    layer.add_polygon(poly);
+fn draw_square(canvas: &mut dyn Canvas, f: &Frame, filled: bool) {
+    f.set_style(canvas, false);
+    shape(
+        canvas,
+        f.polyline(&[(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]),
+        filled,
+    );
 }
 
-fn draw_circle(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    size_pt: f32,
-    filled: bool,
-    color: SymbolColor,
-) {
-    use printpdf::{Point, Polygon};
+fn draw_triangle(canvas: &mut dyn Canvas, f: &Frame, filled: bool) {
+    f.set_style(canvas, false);
+    shape(
+        canvas,
+        f.polyline(&[(-0.5, -0.5), (0.0, 0.5), (0.5, -0.5)]),
+        filled,
+    );
+}
 
-    const C: f32 = 0.55191505;
-    let d = pt_to_mm(size_pt);
-    let r = d / 2.0;
+fn draw_circle(canvas: &mut dyn Canvas, f: &Frame, filled: bool) {
+    const C: f32 = 0.55191505; // bezier approximation constant
+    let r = 0.5;
     let c = r * C;
-    let (x0, y0) = (origin.0 .0, origin.1 .0);
-    let pts = vec![
-        (Point::new(Mm(x0 + r), Mm(y0)), false),
+    let path = f.curves(&[
+        (0.0, -r),
         // bottom-right quarter
-        (Point::new(Mm(x0 + r), Mm(y0)), true),
-        (Point::new(Mm(x0 + r + c), Mm(y0)), true),
-        (Point::new(Mm(x0 + d), Mm(y0 + r - c)), true),
-        (Point::new(Mm(x0 + d), Mm(y0 + r)), false),
+        (c, -r),
+        (r, -c),
+        (r, 0.0),
         // top-right quarter
-        (Point::new(Mm(x0 + d), Mm(y0 + r)), true),
-        (Point::new(Mm(x0 + d), Mm(y0 + r + c)), true),
-        (Point::new(Mm(x0 + r + c), Mm(y0 + d)), true),
-        (Point::new(Mm(x0 + r), Mm(y0 + d)), false),
+        (r, c),
+        (c, r),
+        (0.0, r),
         // top-left quarter
-        (Point::new(Mm(x0 + r), Mm(y0 + d)), true),
-        (Point::new(Mm(x0 + r - c), Mm(y0 + d)), true),
-        (Point::new(Mm(x0), Mm(y0 + r + c)), true),
-        (Point::new(Mm(x0), Mm(y0 + r)), false),
+        (-c, r),
+        (-r, c),
+        (-r, 0.0),
         // bottom-left quarter
-        (Point::new(Mm(x0), Mm(y0 + r)), true),
-        (Point::new(Mm(x0), Mm(y0 + r - c)), true),
-        (Point::new(Mm(x0 + r - c), Mm(y0)), true),
-        (Point::new(Mm(x0 + r), Mm(y0)), false),
-    ];
-
-    let poly = Polygon {
-        rings: vec![pts],
-        mode: if filled { PaintMode::FillStroke } else { PaintMode::Stroke },
-        winding_order: WindingOrder::NonZero,
-    };
+        (-r, -c),
+        (-c, -r),
+        (0.0, -r),
+    ]);
 
-    let col = color.to_color();
-    layer.set_fill_color(col.clone());
-    layer.set_outline_color(col);
-    layer.add_polygon(poly);
+    f.set_style(canvas, false);
+    shape(canvas, path, filled);
 }
 
-fn draw_x(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    size_pt: f32,
-    thick: bool,
-    color: SymbolColor,
-) {
-    use printpdf::{Line, Point};
-    let d = pt_to_mm(size_pt);
-    let (x0, y0) = (origin.0 .0, origin.1 .0);
-    let p1 = Point::new(Mm(x0), Mm(y0));
-    let p2 = Point::new(Mm(x0 + d), Mm(y0 + d));
-    let p3 = Point::new(Mm(x0 + d), Mm(y0));
-    let p4 = Point::new(Mm(x0), Mm(y0 + d));
-
-    let c = color.to_color();
-    layer.set_outline_color(c);
-    layer.set_outline_thickness(if thick { 1.0 } else { 0.5 });
-    layer.add_line(Line { points: vec![(p1, false), (p2, false)], is_closed: false, ..Default::default() });
-    layer.add_line(Line { points: vec![(p3, false), (p4, false)], is_closed: false, ..Default::default() });
-    layer.set_outline_thickness(0.0);
+fn draw_x(canvas: &mut dyn Canvas, f: &Frame, thick: bool) {
+    f.set_style(canvas, thick);
+    canvas.draw_path(&f.polyline(&[(-0.5, -0.5), (0.5, 0.5)]), Paint::Stroke);
+    canvas.draw_path(&f.polyline(&[(0.5, -0.5), (-0.5, 0.5)]), Paint::Stroke);
 }
 
-fn draw_angle(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    size_pt: f32,
-    left: bool,
-    filled: bool,
-    color: SymbolColor,
-) {
-    use printpdf::{Line, Point, Polygon};
-    let d = pt_to_mm(size_pt);
-    let (x0, y0) = (origin.0 .0, origin.1 .0);
-    let (p1, p2, p3) = if left {
-        (
-            Point::new(Mm(x0 + d), Mm(y0)),
-            Point::new(Mm(x0), Mm(y0 + d / 2.0)),
-            Point::new(Mm(x0 + d), Mm(y0 + d)),
-        )
+fn draw_angle(canvas: &mut dyn Canvas, f: &Frame, left: bool, filled: bool) {
+    let s = if left { -1.0 } else { 1.0 };
+    let pts = f.polyline(&[(-0.5 * s, -0.5), (0.5 * s, 0.0), (-0.5 * s, 0.5)]);
+
+    f.set_style(canvas, filled);
+    if filled {
+        shape(canvas, pts, true);
     } else {
+        canvas.draw_path(&pts, Paint::Stroke);
+    }
+}
+
+fn draw_bracket(canvas: &mut dyn Canvas, f: &Frame, left: bool, filled: bool) {
+    // the bracket sits on the outer side of the box; its arms end just
+    // past the center
+    let s = if left { -1.0 } else { 1.0 };
+    let arm = 0.5 - BRACKET_ARM_REACH;
+    f.set_style(canvas, filled);
+    canvas.draw_path(
+        &f.polyline(&[
+            (arm * s, -0.5),
+            (0.5 * s, -0.5),
+            (0.5 * s, 0.5),
+            (arm * s, 0.5),
+        ]),
+        Paint::Stroke,
+    );
+}
+
+fn draw_star(canvas: &mut dyn Canvas, f: &Frame, filled: bool) {
+    // five points, alternating outer and inner radius, starting at the top
+    let pts: Vec<(f32, f32)> = (0..10)
+        .map(|i| {
+            let r = if i % 2 == 0 { STAR_OUTER } else { STAR_INNER };
+            let a = std::f32::consts::FRAC_PI_2 + i as f32 * std::f32::consts::PI / 5.0;
+            (r * a.cos(), r * a.sin())
+        })
+        .collect();
+
+    f.set_style(canvas, false);
+    shape(canvas, f.polyline(&pts), filled);
+}
+
+/// Diagonal arrow pointing down and to the left (or right), used as the
+/// audiogram no-response marker. The filled variant has a solid head.
+fn draw_arrow(canvas: &mut dyn Canvas, f: &Frame, left: bool, filled: bool) {
+    let s = if left { -1.0 } else { 1.0 };
+    let (tail, tip) = ((-0.5 * s, 0.5), (0.5 * s, -0.5));
+
+    // head barbs: the shaft direction, reversed, rotated by +/- 25 degrees
+    let len = std::f32::consts::SQRT_2;
+    let (ux, uy) = ((tail.0 - tip.0) / len, (tail.1 - tip.1) / len);
+    let head = 0.45;
+    let (sin, cos) = 25f32.to_radians().sin_cos();
+    let barb = |sign: f32| {
         (
-            Point::new(Mm(x0), Mm(y0)),
-            Point::new(Mm(x0 + d), Mm(y0 + d / 2.0)),
-            Point::new(Mm(x0), Mm(y0 + d)),
+            tip.0 + head * (ux * cos - sign * uy * sin),
+            tip.1 + head * (sign * ux * sin + uy * cos),
         )
     };
+    let head = f.polyline(&[barb(1.0), tip, barb(-1.0)]);
 
-    let col = color.to_color();
-    layer.set_outline_color(col.clone());
-    layer.set_fill_color(col.clone());
-    layer.set_outline_thickness(if filled { 1.0 } else { 0.5 });
+    f.set_style(canvas, false);
+    canvas.draw_path(&f.polyline(&[tail, tip]), Paint::Stroke);
     if filled {
-        let poly = Polygon {
-            rings: vec![vec![(p1, false), (p2, false), (p3, false)]],
-            mode: PaintMode::FillStroke,
-            winding_order: WindingOrder::NonZero,
-        };
-        layer.add_polygon(poly);
+        shape(canvas, head, true);
     } else {
-        layer.add_line(Line {
-            points: vec![(p1, false), (p2, false), (p3, false)],
-            is_closed: false,
-            ..Default::default()
-        });
+        canvas.draw_path(&head, Paint::Stroke);
     }
-    layer.set_outline_thickness(0.0);
 }
 
-fn draw_bracket(
-    layer: &PdfLayerReference,
-    origin: (Mm, Mm),
-    size_pt: f32,
-    left: bool,
-    filled: bool,
-    color: SymbolColor,
-) {
-    use printpdf::{Line, Point};
-    let d = pt_to_mm(size_pt);
-    let (x0, y0) = (origin.0 .0, origin.1 .0);
-    let h = d * 0.6;
-    let (p1, p2, p3, p4) = if left {
-        (
-            Point::new(Mm(x0 + h), Mm(y0)),
-            Point::new(Mm(x0), Mm(y0)),
-            Point::new(Mm(x0), Mm(y0 + d)),
-            Point::new(Mm(x0 + h), Mm(y0 + d)),
-        )
-    } else {
-        (
-            Point::new(Mm(x0 + d - h), Mm(y0)),
-            Point::new(Mm(x0 + d), Mm(y0)),
-            Point::new(Mm(x0 + d), Mm(y0 + d)),
-            Point::new(Mm(x0 + d - h), Mm(y0 + d)),
-        )
+fn draw_letter(canvas: &mut dyn Canvas, f: &Frame, strokes: &[Stroke], thick: bool) {
+    f.set_style(canvas, thick);
+    for s in strokes {
+        let path = match s {
+            Stroke::Lines(pts) => f.polyline(pts),
+            Stroke::Curves(pts) => f.curves(pts),
+        };
+        canvas.draw_path(&path, Paint::Stroke);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::{Command, PathOp, RecordingCanvas};
+
+    fn record(symbol: Symbol, style: &SymbolStyle) -> RecordingCanvas {
+        let mut canvas = RecordingCanvas::default();
+        draw_symbol(&mut canvas, symbol, (Mm(50.0), Mm(100.0)), style);
+        canvas
+    }
+
+    /// Every point a path passes through or uses as a control point.
+    fn points(path: &Path) -> Vec<(f32, f32)> {
+        path.ops
+            .iter()
+            .flat_map(|op| match *op {
+                PathOp::MoveTo(x, y) | PathOp::LineTo(x, y) => vec![(x, y)],
+                PathOp::CurveTo(a, b, c) => vec![a, b, c],
+                PathOp::Close => vec![],
+            })
+            .collect()
+    }
+
+    const ALL: [Symbol; 30] = {
+        use Symbol::*;
+        [
+            Square,
+            SquareFilled,
+            Triangle,
+            TriangleFilled,
+            Circle,
+            CircleFilled,
+            S,
+            SFilled,
+            U,
+            UFilled,
+            X,
+            XFilled,
+            A,
+            AFilled,
+            Greater,
+            GreaterFilled,
+            Less,
+            LessFilled,
+            LeftBracket,
+            LeftBracketFilled,
+            RightBracket,
+            RightBracketFilled,
+            Star,
+            StarFilled,
+            ArrowDownRight,
+            ArrowDownRightFilled,
+            ArrowDownLeft,
+            ArrowDownLeftFilled,
+            VT,
+            VTFilled,
+        ]
     };
 
-    let col = color.to_color();
-    layer.set_outline_color(col);
-    layer.set_outline_thickness(if filled { 1.0 } else { 0.5 });
-    layer.add_line(Line {
-        points: vec![(p1, false), (p2, false), (p3, false), (p4, false)],
-        is_closed: false,
-        ..Default::default()
-    });
-    layer.set_outline_thickness(0.0);
-}
+    #[test]
+    fn square_is_centered_on_position() {
+        let canvas = record(
+            Symbol::Square,
+            &SymbolStyle::new(72.0 / 25.4 * 4.0, SymbolColor::RED),
+        );
+        let (path, paint) = canvas.paths().next().unwrap();
+        assert_eq!(paint, Paint::Stroke);
+        let pts = points(path);
+        let xs: Vec<f32> = pts.iter().map(|p| p.0).collect();
+        let ys: Vec<f32> = pts.iter().map(|p| p.1).collect();
+        let min = |v: &[f32]| v.iter().cloned().fold(f32::MAX, f32::min);
+        let max = |v: &[f32]| v.iter().cloned().fold(f32::MIN, f32::max);
+        assert!((min(&xs) - 48.0).abs() < 1e-4 && (max(&xs) - 52.0).abs() < 1e-4);
+        assert!((min(&ys) - 98.0).abs() < 1e-4 && (max(&ys) - 102.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn bottom_left_anchor_shifts_by_half_a_box() {
+        let size = 72.0 / 25.4 * 4.0;
+        let style = SymbolStyle {
+            anchor: Anchor::BottomLeft,
+            ..SymbolStyle::new(size, SymbolColor::RED)
+        };
+        let centered = Symbol::Circle.bounding_box(
+            (Mm(52.0), Mm(102.0)),
+            &SymbolStyle::new(size, SymbolColor::RED),
+        );
+        let anchored = Symbol::Circle.bounding_box((Mm(50.0), Mm(100.0)), &style);
+        assert_eq!(centered, anchored);
+    }
+
+    #[test]
+    fn filled_variants_are_filled() {
+        let style = SymbolStyle::new(8.0, SymbolColor::BLUE);
+        for symbol in [
+            Symbol::SquareFilled,
+            Symbol::TriangleFilled,
+            Symbol::CircleFilled,
+            Symbol::StarFilled,
+        ] {
+            let canvas = record(symbol, &style);
+            assert!(
+                canvas.paths().all(|(_, paint)| paint == Paint::FillStroke),
+                "{:?}",
+                symbol
+            );
+        }
+        for symbol in [
+            Symbol::Square,
+            Symbol::Triangle,
+            Symbol::Circle,
+            Symbol::Star,
+        ] {
+            let canvas = record(symbol, &style);
+            assert!(
+                canvas.paths().all(|(_, paint)| paint == Paint::Stroke),
+                "{:?}",
+                symbol
+            );
+        }
+    }
+
+    #[test]
+    fn outline_only_symbols_get_heavier_when_filled() {
+        let style = SymbolStyle::new(8.0, SymbolColor::BLUE);
+        let width = |canvas: &RecordingCanvas| {
+            canvas.commands.iter().find_map(|c| match c {
+                Command::SetOutlineThickness(w) => Some(*w),
+                _ => None,
+            })
+        };
+        assert_eq!(width(&record(Symbol::X, &style)), Some(0.5));
+        assert_eq!(width(&record(Symbol::XFilled, &style)), Some(1.0));
+    }
+
+    #[test]
+    fn circle_is_four_curves() {
+        let canvas = record(Symbol::Circle, &SymbolStyle::new(8.0, SymbolColor::RED));
+        let (path, _) = canvas.paths().next().unwrap();
+        let curves = path
+            .ops
+            .iter()
+            .filter(|op| matches!(op, PathOp::CurveTo(..)))
+            .count();
+        assert_eq!(curves, 4);
+    }
+
+    #[test]
+    fn every_symbol_sets_its_own_state_and_never_resets_it() {
+        let style = SymbolStyle::new(8.0, SymbolColor::Gray(0.5));
+        for symbol in ALL {
+            let canvas = record(symbol, &style);
+            let first_path = canvas
+                .commands
+                .iter()
+                .position(|c| matches!(c, Command::DrawPath(..)))
+                .unwrap();
+            let before = &canvas.commands[..first_path];
+            assert!(
+                before.contains(&Command::SetFillColor(style.color)),
+                "{:?}",
+                symbol
+            );
+            assert!(
+                before.contains(&Command::SetOutlineColor(style.color)),
+                "{:?}",
+                symbol
+            );
+            assert!(before.contains(&Command::SetLineDash(None)), "{:?}", symbol);
+            assert!(
+                !canvas.commands.contains(&Command::SetOutlineThickness(0.0)),
+                "{:?}",
+                symbol
+            );
+        }
+    }
+
+    #[test]
+    fn drawing_stays_inside_bounding_box() {
+        let style = SymbolStyle::new(8.0, SymbolColor::RED);
+        for symbol in ALL {
+            let bbox = symbol.bounding_box((Mm(50.0), Mm(100.0)), &style);
+            let canvas = record(symbol, &style);
+            for (path, _) in canvas.paths() {
+                for (x, y) in points(path) {
+                    assert!(
+                        x >= bbox.left && x <= bbox.right && y >= bbox.bottom && y <= bbox.top,
+                        "{:?}: ({}, {}) outside {:?}",
+                        symbol,
+                        x,
+                        y,
+                        bbox
+                    );
+                }
+            }
+        }
+    }
 
+    #[test]
+    fn every_symbol_is_wrapped_in_save_and_restore() {
+        let style = SymbolStyle::new(8.0, SymbolColor::RED);
+        for symbol in ALL {
+            let canvas = record(symbol, &style);
+            assert_eq!(
+                canvas.commands.first(),
+                Some(&Command::SaveGraphicsState),
+                "{:?}",
+                symbol
+            );
+            assert_eq!(
+                canvas.commands.last(),
+                Some(&Command::RestoreGraphicsState),
+                "{:?}",
+                symbol
+            );
+            let saves = canvas
+                .commands
+                .iter()
+                .filter(|c| **c == Command::SaveGraphicsState)
+                .count();
+            assert_eq!(saves, 1, "{:?}", symbol);
+        }
+    }
+}
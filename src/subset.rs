@@ -0,0 +1,329 @@
+//! TrueType font subsetting, to embed only the glyphs a report draws.
+//!
+//! [`subset`] keeps every glyph id where it is and empties the outlines of
+//! the glyphs not needed, so text already mapped to glyphs against the full
+//! font (kerned runs are written as glyph ids) stays valid. The `cmap` is cut
+//! down to the characters drawn too, which also keeps the PDF's `ToUnicode`
+//! and width arrays, built from it, short. Layout tables the PDF viewer
+//! never reads (`GPOS`, `GSUB`, `kern`, ...) and the glyph names in `post`
+//! are dropped.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use owned_ttf_parser::Face;
+
+/// Tables only text shaping reads, or that describe glyphs no longer there.
+const DROPPED_TABLES: [&[u8; 4]; 11] = [
+    b"BASE", b"DSIG", b"GDEF", b"GPOS", b"GSUB", b"JSTF", b"LTSH", b"MATH", b"VDMX", b"hdmx",
+    b"kern",
+];
+
+// composite glyph flags
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// `font` reduced to the glyphs of `chars`, plus `.notdef` and the parts
+/// of composite glyphs. `None` for fonts this cannot subset (CFF outlines,
+/// collections or damaged tables), which are best embedded whole.
+pub fn subset(font: &[u8], chars: &BTreeSet<char>) -> Option<Vec<u8>> {
+    let face = Face::parse(font, 0).ok()?;
+    let mapping: BTreeMap<u32, u16> = chars
+        .iter()
+        .filter_map(|&c| Some((c as u32, face.glyph_index(c)?.0)))
+        .collect();
+
+    let mut tables = read_tables(font)?;
+    let head = tables.get(b"head")?;
+    let long_offsets = read_u16(head, 50)? == 1;
+    let glyph_count = read_u16(tables.get(b"maxp")?, 4)? as usize;
+    let loca = tables.get(b"loca")?;
+    let offsets = (0..=glyph_count)
+        .map(|i| match long_offsets {
+            true => read_u32(loca, 4 * i).map(|o| o as usize),
+            false => read_u16(loca, 2 * i).map(|o| 2 * o as usize),
+        })
+        .collect::<Option<Vec<usize>>>()?;
+    let glyf = tables.get(b"glyf")?;
+    let outline =
+        |gid: usize| -> Option<&[u8]> { glyf.get(*offsets.get(gid)?..*offsets.get(gid + 1)?) };
+
+    // .notdef, the mapped glyphs and, through composites, their parts
+    let mut used = BTreeSet::from([0u16]);
+    let mut pending: Vec<u16> = std::iter::once(0)
+        .chain(mapping.values().copied())
+        .collect();
+    while let Some(gid) = pending.pop() {
+        used.insert(gid);
+        for part in components(outline(gid as usize)?)? {
+            if (part as usize) < glyph_count && !used.contains(&part) {
+                pending.push(part);
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity(4 * (glyph_count + 1));
+    for gid in 0..glyph_count {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        if used.contains(&(gid as u16)) {
+            new_glyf.extend(outline(gid)?);
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    new_head[8..12].copy_from_slice(&[0; 4]); // checkSumAdjustment, redone last
+    new_head[50..52].copy_from_slice(&1u16.to_be_bytes()); // long loca offsets
+    let post = tables.get(b"post").map(|post| post_without_names(post));
+
+    tables.retain(|tag, _| !DROPPED_TABLES.contains(&tag));
+    tables.insert(*b"cmap", cmap(&mapping));
+    tables.insert(*b"glyf", new_glyf);
+    tables.insert(*b"loca", new_loca);
+    tables.insert(*b"head", new_head);
+    if let Some(post) = post {
+        tables.insert(*b"post", post?);
+    }
+    Some(write_font(font.get(0..4)?, tables))
+}
+
+/// Glyph ids a composite glyph is built from; none for simple glyphs.
+fn components(outline: &[u8]) -> Option<Vec<u16>> {
+    if outline.is_empty() || read_u16(outline, 0)? as i16 >= 0 {
+        return Some(Vec::new());
+    }
+    let mut parts = Vec::new();
+    let mut pos = 10;
+    loop {
+        let flags = read_u16(outline, pos)?;
+        parts.push(read_u16(outline, pos + 2)?);
+        pos += 4;
+        pos += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            4
+        } else {
+            2
+        };
+        pos += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(parts);
+        }
+    }
+}
+
+/// Table data by tag, from a single-font file's table directory.
+fn read_tables(font: &[u8]) -> Option<BTreeMap<[u8; 4], Vec<u8>>> {
+    let version = read_u32(font, 0)?;
+    // TrueType outlines only: no 'OTTO' (CFF) or 'ttcf' (collection)
+    if version != 0x0001_0000 && version != u32::from_be_bytes(*b"true") {
+        return None;
+    }
+    let count = read_u16(font, 4)? as usize;
+    (0..count)
+        .map(|i| {
+            let record = 12 + 16 * i;
+            let tag: [u8; 4] = font.get(record..record + 4)?.try_into().ok()?;
+            let offset = read_u32(font, record + 8)? as usize;
+            let length = read_u32(font, record + 12)? as usize;
+            Some((tag, font.get(offset..offset + length)?.to_vec()))
+        })
+        .collect()
+}
+
+/// Format 3 `post` table: the same metrics, no glyph names.
+fn post_without_names(post: &[u8]) -> Option<Vec<u8>> {
+    let mut header = post.get(0..32)?.to_vec();
+    header[0..4].copy_from_slice(&0x0003_0000u32.to_be_bytes());
+    Some(header)
+}
+
+/// A Windows Unicode `cmap` for `mapping`: format 4 for the BMP, plus
+/// format 12 when characters beyond it are mapped.
+fn cmap(mapping: &BTreeMap<u32, u16>) -> Vec<u8> {
+    // runs of consecutive characters on consecutive glyphs
+    let mut runs: Vec<(u32, u32, u16)> = Vec::new();
+    for (&code, &gid) in mapping {
+        match runs.last_mut() {
+            Some((start, end, start_gid))
+                if *end + 1 == code && (*start_gid as u32 + (code - *start)) == gid as u32 =>
+            {
+                *end = code;
+            }
+            _ => runs.push((code, code, gid)),
+        }
+    }
+
+    let bmp: Vec<_> = runs.iter().filter(|run| run.1 <= 0xFFFF).collect();
+    let mut format4 = Vec::new();
+    let segments = bmp.len() + 1;
+    let search_range = 2 * 2u16.pow(segments.ilog2());
+    for value in [
+        4,
+        (16 + 8 * segments) as u16,
+        0,
+        2 * segments as u16,
+        search_range,
+        segments.ilog2() as u16,
+        2 * segments as u16 - search_range,
+    ] {
+        format4.extend(value.to_be_bytes());
+    }
+    let ends = bmp.iter().map(|run| run.1 as u16).chain([0xFFFF]);
+    format4.extend(ends.flat_map(u16::to_be_bytes));
+    format4.extend(0u16.to_be_bytes());
+    let starts = bmp.iter().map(|run| run.0 as u16).chain([0xFFFF]);
+    format4.extend(starts.flat_map(u16::to_be_bytes));
+    let deltas = bmp
+        .iter()
+        .map(|run| run.2.wrapping_sub(run.0 as u16))
+        .chain([1]);
+    format4.extend(deltas.flat_map(u16::to_be_bytes));
+    format4.extend(std::iter::repeat_n(0, 2 * segments));
+
+    let mut subtables = vec![(1u16, format4)];
+    if runs.iter().any(|run| run.1 > 0xFFFF) {
+        let mut format12 = Vec::new();
+        format12.extend(12u16.to_be_bytes());
+        format12.extend(0u16.to_be_bytes());
+        format12.extend((16 + 12 * runs.len() as u32).to_be_bytes());
+        format12.extend(0u32.to_be_bytes());
+        format12.extend((runs.len() as u32).to_be_bytes());
+        for &(start, end, gid) in &runs {
+            format12.extend(start.to_be_bytes());
+            format12.extend(end.to_be_bytes());
+            format12.extend((gid as u32).to_be_bytes());
+        }
+        subtables.push((10, format12));
+    }
+
+    let mut cmap = Vec::new();
+    cmap.extend(0u16.to_be_bytes());
+    cmap.extend((subtables.len() as u16).to_be_bytes());
+    let mut offset = 4 + 8 * subtables.len() as u32;
+    for (encoding, data) in &subtables {
+        cmap.extend(3u16.to_be_bytes());
+        cmap.extend(encoding.to_be_bytes());
+        cmap.extend(offset.to_be_bytes());
+        offset += data.len() as u32;
+    }
+    for (_, data) in subtables {
+        cmap.extend(data);
+    }
+    cmap
+}
+
+/// Assemble a font file from its tables, with checksums.
+fn write_font(version: &[u8], tables: BTreeMap<[u8; 4], Vec<u8>>) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let search_range = 16 * 2u16.pow(count.ilog2());
+    let mut font = version.to_vec();
+    for value in [
+        count,
+        search_range,
+        count.ilog2() as u16,
+        16 * count - search_range,
+    ] {
+        font.extend(value.to_be_bytes());
+    }
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = Some(offset);
+        }
+        font.extend(tag);
+        font.extend(checksum(data).to_be_bytes());
+        font.extend((offset as u32).to_be_bytes());
+        font.extend((data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for data in tables.values() {
+        font.extend(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+
+    // the head checksum is computed with the adjustment zeroed, as it is
+    // here, then the adjustment makes the whole file sum to a constant
+    if let Some(head) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&font));
+        font[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    font
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTO: &[u8] = include_bytes!("../vendor/printpdf/assets/fonts/RobotoMedium.ttf");
+
+    #[test]
+    fn subset_keeps_the_glyphs_of_its_characters() {
+        let chars: BTreeSet<char> = "Audiogramme ÉÀ Δ".chars().collect();
+        let bytes = subset(ROBOTO, &chars).unwrap();
+        assert!(bytes.len() < ROBOTO.len() / 2, "{} bytes", bytes.len());
+
+        let full = Face::parse(ROBOTO, 0).unwrap();
+        let small = Face::parse(&bytes, 0).unwrap();
+        for c in chars {
+            let gid = full.glyph_index(c).unwrap();
+            assert_eq!(small.glyph_index(c), Some(gid), "{:?}", c);
+            assert_eq!(small.glyph_hor_advance(gid), full.glyph_hor_advance(gid));
+            assert_eq!(
+                small.glyph_bounding_box(gid),
+                full.glyph_bounding_box(gid),
+                "{:?}",
+                c
+            );
+        }
+        // not drawn: no longer mapped, its outline emptied
+        let z = full.glyph_index('z').unwrap();
+        assert_eq!(small.glyph_index('z'), None);
+        assert_eq!(small.glyph_bounding_box(z), None);
+    }
+
+    #[test]
+    fn subset_keeps_composite_parts() {
+        // Roboto builds É from E and an acute accent
+        let chars = BTreeSet::from(['É']);
+        let bytes = subset(ROBOTO, &chars).unwrap();
+        let full = Face::parse(ROBOTO, 0).unwrap();
+        let small = Face::parse(&bytes, 0).unwrap();
+        let e = full.glyph_index('E').unwrap();
+        assert_eq!(small.glyph_bounding_box(e), full.glyph_bounding_box(e));
+    }
+
+    #[test]
+    fn subset_refuses_what_it_cannot_read() {
+        assert_eq!(subset(b"OTTO\0\0\0\0\0\0\0\0", &BTreeSet::new()), None);
+        assert_eq!(subset(&ROBOTO[..200], &BTreeSet::new()), None);
+    }
+}
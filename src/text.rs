@@ -0,0 +1,1373 @@
+//! Text measurement on top of a loaded TrueType face.
+//!
+//! [`TextMeasurer`] maps a string to glyphs the same way the PDF writer does
+//! (one glyph per character through the `cmap`), then applies pair kerning
+//! from the `GPOS` `kern` feature, or from the legacy `kern` table for fonts
+//! that only have that. Widths and vertical metrics come back in millimetres
+//! for a given font size in points, so they can be used directly for layout.
+//!
+//! The builtin PDF Helvetica is measured from its published widths, so the
+//! default report lays out the same way as one with an embedded font.
+//!
+//! Characters the face cannot map are taken, one by one, from fallback faces
+//! chained with [`TextMeasurer::with_fallback`], typically a broad Unicode
+//! font behind the one the report is designed for. Characters no face can
+//! map are an error rather than being dropped: a missing glyph would
+//! otherwise shift everything after it.
+//!
+//! [`draw_text_in_box`] builds on the measurements to align a line inside a
+//! rectangle, and [`draw_paragraph`] to wrap longer text into one.
+
+use std::fmt;
+use std::ops::Range;
+
+use owned_ttf_parser::gpos::{PairAdjustment, PositioningSubtable};
+use owned_ttf_parser::{AsFaceRef, Face, FaceParsingError, GlyphId, OwnedFace, Tag};
+
+use printpdf::{BuiltinFont, Mm};
+
+use crate::canvas::Canvas;
+use crate::shapes::{pt_to_mm, BoundingBox};
+
+#[derive(Debug)]
+pub enum Error {
+    /// The font data could not be parsed.
+    Font(FaceParsingError),
+    /// Neither the face nor any of its fallbacks has a glyph for this
+    /// character.
+    Unmappable(char),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Font(e) => write!(f, "invalid font: {}", e),
+            Error::Unmappable(c) => {
+                write!(f, "no font has a glyph for {:?} (U+{:04X})", c, *c as u32)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Font(e) => Some(e),
+            Error::Unmappable(_) => None,
+        }
+    }
+}
+
+impl From<FaceParsingError> for Error {
+    fn from(e: FaceParsingError) -> Self {
+        Error::Font(e)
+    }
+}
+
+/// A glyph of a measured string, in font units.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Glyph {
+    pub id: GlyphId,
+    /// Id of the face the glyph comes from, which is also the canvas font
+    /// it is drawn in: its position in the [`FontRegistry`] the
+    /// [`TextMeasurer`] was built from, or in the fallback chain of one
+    /// built by hand.
+    ///
+    /// [`FontRegistry`]: crate::fonts::FontRegistry
+    pub font: usize,
+    /// Horizontal advance from the `hmtx` table.
+    pub advance: u16,
+    /// Pair adjustment between this glyph and the next one (negative to
+    /// tighten). Always 0 before a glyph from another face.
+    pub kerning: i16,
+}
+
+/// Metrics of a string set at a given size, in millimetres.
+///
+/// Vertical values are relative to the baseline and the bounding box to the
+/// pen position, so `ascent` and `bbox.top` are positive and `descent` is the
+/// (positive) distance below the baseline.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextMetrics {
+    /// Advance width including kerning.
+    pub width: f32,
+    /// Ascender of the primary face, regardless of the characters in the
+    /// string.
+    pub ascent: f32,
+    /// Descender of the primary face, regardless of the characters in the
+    /// string.
+    pub descent: f32,
+    /// Ink extent of the glyphs actually drawn; empty at the origin for
+    /// strings with no visible glyphs.
+    pub bbox: BoundingBox,
+}
+
+impl TextMetrics {
+    /// Line height from the font's ascender to its descender.
+    pub fn height(&self) -> f32 {
+        self.ascent + self.descent
+    }
+}
+
+/// Measures strings with a font face and, for characters it has no glyph
+/// for, the fallback faces chained after it.
+pub struct TextMeasurer {
+    /// Face ids and faces: primary first, then fallbacks in the order they
+    /// are tried.
+    fonts: Vec<(usize, Source)>,
+}
+
+enum Source {
+    Face {
+        face: OwnedFace,
+        /// GPOS lookups referenced by the `kern` feature, in lookup order.
+        kern_lookups: Vec<u16>,
+    },
+    /// A PDF builtin Helvetica face, from its published metrics.
+    Helvetica { bold: bool },
+}
+
+impl TextMeasurer {
+    /// Parse the first face of a TrueType/OpenType font.
+    pub fn new(font: Vec<u8>) -> Result<Self, Error> {
+        let face = OwnedFace::from_vec(font, 0)?;
+        let kern_lookups = kern_lookups(face.as_face_ref());
+        Ok(TextMeasurer {
+            fonts: vec![(0, Source::Face { face, kern_lookups })],
+        })
+    }
+
+    /// Metrics of the builtin PDF Helvetica, which only covers the WinAnsi
+    /// character set. Glyph ids are WinAnsi codes and there is no kerning,
+    /// matching what the PDF writer emits for builtin fonts.
+    pub fn helvetica() -> Self {
+        TextMeasurer::builtin(BuiltinFont::Helvetica).expect("Helvetica metrics")
+    }
+
+    /// Metrics of a builtin Helvetica face, as for [`TextMeasurer::helvetica`].
+    /// `None` for the other builtin fonts, whose metrics are not included.
+    /// The oblique faces share the upright widths.
+    pub fn builtin(font: BuiltinFont) -> Option<Self> {
+        let bold = match font {
+            BuiltinFont::Helvetica | BuiltinFont::HelveticaOblique => false,
+            BuiltinFont::HelveticaBold | BuiltinFont::HelveticaBoldOblique => true,
+            _ => return None,
+        };
+        Some(TextMeasurer {
+            fonts: vec![(0, Source::Helvetica { bold })],
+        })
+    }
+
+    /// Single-face measurers chained in order, each face taking the given
+    /// id.
+    pub(crate) fn with_ids(faces: impl IntoIterator<Item = (usize, TextMeasurer)>) -> Self {
+        TextMeasurer {
+            fonts: faces
+                .into_iter()
+                .flat_map(|(id, measurer)| {
+                    measurer
+                        .fonts
+                        .into_iter()
+                        .map(move |(_, source)| (id, source))
+                })
+                .collect(),
+        }
+    }
+
+    /// Chain the faces of `fallback` after this one's: characters none of
+    /// the faces so far can map are looked up there, one at a time. Their
+    /// ids are renumbered to follow this one's.
+    pub fn with_fallback(mut self, fallback: TextMeasurer) -> Self {
+        let offset = self.fonts.iter().map(|&(id, _)| id + 1).max().unwrap_or(0);
+        self.fonts.extend(
+            fallback
+                .fonts
+                .into_iter()
+                .map(|(id, source)| (offset + id, source)),
+        );
+        self
+    }
+
+    /// Units per em of face `font`.
+    pub fn units_per_em(&self, font: usize) -> u16 {
+        self.source(font).units_per_em()
+    }
+
+    fn source(&self, font: usize) -> &Source {
+        self.fonts
+            .iter()
+            .find(|&&(id, _)| id == font)
+            .map(|(_, source)| source)
+            .expect("face id not in this measurer")
+    }
+
+    /// Map `text` to glyphs, each from the first face that has one, and
+    /// their pair kerning.
+    pub fn glyphs(&self, text: &str) -> Result<Vec<Glyph>, Error> {
+        let mut glyphs = text
+            .chars()
+            .map(|c| {
+                self.fonts
+                    .iter()
+                    .find_map(|&(font, ref source)| {
+                        let (id, advance) = source.glyph(c)?;
+                        Some(Glyph {
+                            id,
+                            font,
+                            advance,
+                            kerning: 0,
+                        })
+                    })
+                    .ok_or(Error::Unmappable(c))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        for i in 1..glyphs.len() {
+            glyphs[i - 1].kerning = self.kerning(&glyphs[i - 1], &glyphs[i]);
+        }
+        Ok(glyphs)
+    }
+
+    /// Millimetres per font unit of `font` at `font_size` points.
+    fn scale(&self, font: usize, font_size: f32) -> f32 {
+        pt_to_mm(font_size / self.units_per_em(font) as f32)
+    }
+
+    /// Measure `text` set at `font_size` points.
+    pub fn measure(&self, text: &str, font_size: f32) -> Result<TextMetrics, Error> {
+        let mut pen = 0.0;
+        let mut ink: Option<BoundingBox> = None;
+        for glyph in self.glyphs(text)? {
+            let scale = self.scale(glyph.font, font_size);
+            if let Some((l, b, r, t)) = self.source(glyph.font).glyph_box(&glyph) {
+                let glyph_ink = BoundingBox {
+                    left: pen + scale * l as f32,
+                    bottom: scale * b as f32,
+                    right: pen + scale * r as f32,
+                    top: scale * t as f32,
+                };
+                ink = Some(match ink {
+                    Some(ink) => BoundingBox {
+                        left: ink.left.min(glyph_ink.left),
+                        bottom: ink.bottom.min(glyph_ink.bottom),
+                        right: ink.right.max(glyph_ink.right),
+                        top: ink.top.max(glyph_ink.top),
+                    },
+                    None => glyph_ink,
+                });
+            }
+            pen += scale * (glyph.advance as i32 + glyph.kerning as i32) as f32;
+        }
+
+        let (primary, source) = &self.fonts[0];
+        let (ascender, descender) = source.vertical_metrics();
+        let scale = self.scale(*primary, font_size);
+        Ok(TextMetrics {
+            width: pen,
+            ascent: scale * ascender as f32,
+            descent: scale * -descender as f32,
+            bbox: ink.unwrap_or(BoundingBox {
+                left: 0.0,
+                bottom: 0.0,
+                right: 0.0,
+                top: 0.0,
+            }),
+        })
+    }
+
+    /// Advance width of `text` at `font_size` points, in millimetres.
+    pub fn width(&self, text: &str, font_size: f32) -> Result<f32, Error> {
+        Ok(self.measure(text, font_size)?.width)
+    }
+
+    /// Horizontal adjustment between two glyphs, in font units. Glyphs from
+    /// different faces are never kerned.
+    pub fn kerning(&self, left: &Glyph, right: &Glyph) -> i16 {
+        if left.font != right.font {
+            return 0;
+        }
+        self.source(left.font).kerning(left.id, right.id)
+    }
+}
+
+impl Source {
+    fn units_per_em(&self) -> u16 {
+        match self {
+            Source::Face { face, .. } => face.as_face_ref().units_per_em(),
+            Source::Helvetica { .. } => 1000,
+        }
+    }
+
+    /// Font-wide ascender and descender, in font units.
+    fn vertical_metrics(&self) -> (i16, i16) {
+        match self {
+            Source::Face { face, .. } => {
+                let face = face.as_face_ref();
+                (face.ascender(), face.descender())
+            }
+            Source::Helvetica { .. } => (HELVETICA_ASCENDER, HELVETICA_DESCENDER),
+        }
+    }
+
+    /// Glyph and advance for `c`, if the face has one.
+    fn glyph(&self, c: char) -> Option<(GlyphId, u16)> {
+        match self {
+            Source::Face { face, .. } => {
+                let face = face.as_face_ref();
+                let id = face.glyph_index(c)?;
+                Some((id, face.glyph_hor_advance(id).unwrap_or(0)))
+            }
+            &Source::Helvetica { bold } => {
+                let (code, advance) = helvetica_glyph(c, bold)?;
+                Some((GlyphId(code as u16), advance))
+            }
+        }
+    }
+
+    /// Ink box of a glyph relative to its origin, in font units.
+    fn glyph_box(&self, glyph: &Glyph) -> Option<(i32, i32, i32, i32)> {
+        match self {
+            Source::Face { face, .. } => {
+                let r = face.as_face_ref().glyph_bounding_box(glyph.id)?;
+                Some((
+                    r.x_min as i32,
+                    r.y_min as i32,
+                    r.x_max as i32,
+                    r.y_max as i32,
+                ))
+            }
+            // No outlines to look at: spaces are blank and everything else
+            // fills its advance between descender and ascender
+            Source::Helvetica { .. } if matches!(glyph.id.0, 0x20 | 0xA0) => None,
+            Source::Helvetica { .. } => Some((
+                0,
+                HELVETICA_DESCENDER as i32,
+                glyph.advance as i32,
+                HELVETICA_ASCENDER as i32,
+            )),
+        }
+    }
+
+    /// GPOS wins when the font has a `kern` feature; the legacy table is only
+    /// consulted otherwise, as shaping engines do.
+    fn kerning(&self, left: GlyphId, right: GlyphId) -> i16 {
+        let Source::Face { face, kern_lookups } = self else {
+            return 0;
+        };
+        let face = face.as_face_ref();
+        match face.tables().gpos {
+            Some(gpos) if !kern_lookups.is_empty() => kern_lookups
+                .iter()
+                .filter_map(|&index| gpos.lookups.get(index))
+                .filter_map(|lookup| {
+                    // the first subtable covering the pair applies
+                    lookup
+                        .subtables
+                        .into_iter::<PositioningSubtable>()
+                        .find_map(|subtable| match subtable {
+                            PositioningSubtable::Pair(pair) => pair_kerning(&pair, left, right),
+                            _ => None,
+                        })
+                })
+                .fold(0i16, |sum, k| sum.saturating_add(k)),
+            _ => face
+                .tables()
+                .kern
+                .and_then(|kern| {
+                    kern.subtables
+                        .into_iter()
+                        .filter(|s| s.horizontal && !s.variable && !s.has_cross_stream)
+                        .find_map(|s| s.glyphs_kerning(left, right))
+                })
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Lookup indices of every `kern` feature in the GPOS table.
+fn kern_lookups(face: &Face) -> Vec<u16> {
+    let Some(gpos) = face.tables().gpos else {
+        return Vec::new();
+    };
+    let kern = Tag::from_bytes(b"kern");
+    let mut lookups: Vec<u16> = (0..gpos.features.len())
+        .filter_map(|i| gpos.features.get(i))
+        .filter(|feature| feature.tag == kern)
+        .flat_map(|feature| feature.lookup_indices)
+        .collect();
+    lookups.sort_unstable();
+    lookups.dedup();
+    lookups
+}
+
+fn pair_kerning(pair: &PairAdjustment, left: GlyphId, right: GlyphId) -> Option<i16> {
+    match pair {
+        PairAdjustment::Format1 { coverage, sets } => {
+            let set = sets.get(coverage.get(left)?)?;
+            set.get(right).map(|(first, _)| first.x_advance)
+        }
+        PairAdjustment::Format2 {
+            coverage,
+            classes,
+            matrix,
+        } => {
+            coverage.get(left)?;
+            let classes = (classes.0.get(left), classes.1.get(right));
+            matrix.get(classes).map(|(first, _)| first.x_advance)
+        }
+    }
+}
+
+/// Horizontal placement of a line inside its box.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum HAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Words spread to both edges; a single word stays on the left.
+    Justify,
+}
+
+/// Vertical placement of a line inside its box, by the font's ascender and
+/// descender so lines with and without descenders sit at the same height.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum VAlign {
+    Top,
+    #[default]
+    Middle,
+    Bottom,
+    /// Baseline on the bottom edge, for a line that must share its baseline
+    /// with text next to it.
+    Baseline,
+}
+
+/// Draw one line of `text` at `font_size` points inside `rect` (page
+/// millimetres). `font` must measure the font the canvas draws text with.
+///
+/// Text wider than the box is not clipped: centered text overflows on both
+/// sides, right-aligned text to the left.
+pub fn draw_text_in_box(
+    canvas: &mut dyn Canvas,
+    text: &str,
+    rect: &BoundingBox,
+    h_align: HAlign,
+    v_align: VAlign,
+    font: &TextMeasurer,
+    font_size: f32,
+) -> Result<(), Error> {
+    let metrics = font.measure(text, font_size)?;
+    let baseline = match v_align {
+        VAlign::Top => rect.top - metrics.ascent,
+        VAlign::Middle => rect.center().1 - (metrics.ascent - metrics.descent) / 2.0,
+        VAlign::Bottom => rect.bottom + metrics.descent,
+        VAlign::Baseline => rect.bottom,
+    };
+
+    if h_align == HAlign::Justify {
+        let words: Vec<&str> = text.split(' ').filter(|w| !w.is_empty()).collect();
+        if words.len() > 1 {
+            let widths = words
+                .iter()
+                .map(|w| font.width(w, font_size))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let space = font.width(" ", font_size)?;
+            let gap =
+                ((rect.width() - widths.iter().sum::<f32>()) / (words.len() - 1) as f32).max(space);
+            let mut x = rect.left;
+            for (word, width) in words.iter().zip(widths) {
+                draw_run(canvas, word, font, font_size, x, baseline)?;
+                x += width + gap;
+            }
+            return Ok(());
+        }
+    }
+
+    let x = match h_align {
+        HAlign::Left | HAlign::Justify => rect.left,
+        HAlign::Center => rect.center().0 - metrics.width / 2.0,
+        HAlign::Right => rect.right - metrics.width,
+    };
+    draw_run(canvas, text, font, font_size, x, baseline)
+}
+
+/// Text starting at `x` on the `baseline`, with its kerning, drawn as one
+/// run per stretch of glyphs from the same face.
+fn draw_run(
+    canvas: &mut dyn Canvas,
+    text: &str,
+    font: &TextMeasurer,
+    font_size: f32,
+    x: f32,
+    baseline: f32,
+) -> Result<(), Error> {
+    let glyphs = font.glyphs(text)?;
+    // glyphs map one to one to characters
+    let offsets: Vec<usize> = text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([text.len()])
+        .collect();
+    let mut x = x;
+    let mut start = 0;
+    while start < glyphs.len() {
+        let face = glyphs[start].font;
+        let end = glyphs[start..]
+            .iter()
+            .position(|g| g.font != face)
+            .map_or(glyphs.len(), |n| start + n);
+        let run = &glyphs[start..end];
+        canvas.use_kerned_text(
+            &text[offsets[start]..offsets[end]],
+            run,
+            font.units_per_em(face),
+            font_size,
+            Mm(x),
+            Mm(baseline),
+        );
+        let units: i32 = run
+            .iter()
+            .map(|g| g.advance as i32 + g.kerning as i32)
+            .sum();
+        x += font.scale(face, font_size) * units as f32;
+        start = end;
+    }
+    Ok(())
+}
+
+/// What [`draw_paragraph`] does with lines that do not fit in the box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Overflow {
+    /// Step the font size down until everything fits, but not below
+    /// `min_size` points; anything still left over is cut with an ellipsis.
+    Shrink { min_size: f32 },
+    /// Cut the last line that fits with an ellipsis.
+    Ellipsis,
+    /// Stop at the last line that fits and hand back the rest, to be drawn
+    /// somewhere else (usually on another page).
+    Continue,
+}
+
+/// How [`draw_paragraph`] sets text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ParagraphStyle {
+    /// Font size in points.
+    pub font_size: f32,
+    /// Baseline-to-baseline distance, as a multiple of the font size.
+    pub line_spacing: f32,
+    /// Justified paragraphs keep their last line on the left.
+    pub align: HAlign,
+    /// Placement of the drawn lines as a block; with
+    /// [`VAlign::Baseline`] the last line sits on the bottom edge.
+    pub v_align: VAlign,
+    pub overflow: Overflow,
+}
+
+impl ParagraphStyle {
+    /// Left-aligned, single-spaced text from the top of the box, cut with an
+    /// ellipsis.
+    pub fn new(font_size: f32) -> Self {
+        ParagraphStyle {
+            font_size,
+            line_spacing: 1.2,
+            align: HAlign::Left,
+            v_align: VAlign::Top,
+            overflow: Overflow::Ellipsis,
+        }
+    }
+}
+
+// Size decrement for Overflow::Shrink, in points
+const SHRINK_STEP: f32 = 0.5;
+
+/// A wrapped line, as a byte range into the paragraph text.
+struct Line {
+    range: Range<usize>,
+    /// Followed by a line break in the text, or the end of the text.
+    ends_paragraph: bool,
+}
+
+/// Split `text` into lines no wider than `max_width` millimetres. Line
+/// breaks in the text are kept; a word too long for a line on its own is
+/// split between characters.
+pub fn wrap_lines<'a>(
+    text: &'a str,
+    max_width: f32,
+    font: &TextMeasurer,
+    font_size: f32,
+) -> Result<Vec<&'a str>, Error> {
+    Ok(wrap(text, max_width, font, font_size)?
+        .into_iter()
+        .map(|line| text[line.range].trim_end())
+        .collect())
+}
+
+fn wrap(
+    text: &str,
+    max_width: f32,
+    font: &TextMeasurer,
+    font_size: f32,
+) -> Result<Vec<Line>, Error> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for paragraph in text.split('\n') {
+        let range = offset..offset + paragraph.len();
+        offset = range.end + 1;
+        wrap_paragraph(text, range, max_width, font, font_size, &mut lines)?;
+    }
+    Ok(lines)
+}
+
+/// Greedy line filling of one paragraph of `text`.
+fn wrap_paragraph(
+    text: &str,
+    range: Range<usize>,
+    max_width: f32,
+    font: &TextMeasurer,
+    font_size: f32,
+    lines: &mut Vec<Line>,
+) -> Result<(), Error> {
+    let fits = |r: Range<usize>| -> Result<bool, Error> {
+        Ok(font.width(text[r].trim_end(), font_size)? <= max_width)
+    };
+    let breaks = break_opportunities(&text[range.clone()])
+        .into_iter()
+        .map(|(end, next)| (range.start + end, range.start + next))
+        .chain([(range.end, range.end)]);
+
+    let mut start = range.start;
+    let mut fitting: Option<(usize, usize)> = None;
+    for (end, next) in breaks {
+        if fits(start..end)? {
+            fitting = Some((end, next));
+            continue;
+        }
+        if let Some((end, next)) = fitting {
+            lines.push(Line {
+                range: start..end,
+                ends_paragraph: false,
+            });
+            start = next;
+        }
+        while !fits(start..end)? {
+            let split = split_point(text, start..end, max_width, font, font_size)?;
+            lines.push(Line {
+                range: start..split,
+                ends_paragraph: false,
+            });
+            start = split;
+        }
+        fitting = Some((end, next));
+    }
+    lines.push(Line {
+        range: start..range.end,
+        ends_paragraph: true,
+    });
+    Ok(())
+}
+
+/// End of the longest prefix of `text[range]` that fits in `max_width`,
+/// keeping at least one character so wrapping always moves on.
+fn split_point(
+    text: &str,
+    range: Range<usize>,
+    max_width: f32,
+    font: &TextMeasurer,
+    font_size: f32,
+) -> Result<usize, Error> {
+    let mut split = None;
+    for (i, c) in text[range.clone()].char_indices() {
+        let end = range.start + i + c.len_utf8();
+        if split.is_some() && font.width(&text[range.start..end], font_size)? > max_width {
+            break;
+        }
+        split = Some(end);
+    }
+    Ok(split.unwrap_or(range.end))
+}
+
+/// Punctuation that French typography separates from the preceding word by
+/// a space, which must not become a line break.
+const NO_BREAK_BEFORE: &[char] = &[':', ';', '!', '?', '»', '%'];
+
+/// Places `text` may be broken, as (end of this line, start of the next)
+/// byte offsets. Lines break at ordinary spaces, which are dropped, and
+/// after a hyphen between letters, which stays on the first line. No-break
+/// spaces never break, and neither does the space inside French guillemets
+/// or before high punctuation ("mot ?", "« mot »").
+fn break_opportunities(text: &str) -> Vec<(usize, usize)> {
+    let mut breaks = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            ' ' => {
+                let mut next = i + 1;
+                while let Some(&(j, ' ')) = chars.peek() {
+                    next = j + 1;
+                    chars.next();
+                }
+                let after = chars.peek().map(|&(_, c)| c);
+                let glued =
+                    prev == Some('«') || after.is_some_and(|c| NO_BREAK_BEFORE.contains(&c));
+                if !glued && prev.is_some() {
+                    breaks.push((i, next));
+                }
+            }
+            '-' => {
+                let after = chars.peek().map(|&(_, c)| c);
+                if prev.is_some_and(char::is_alphabetic) && after.is_some_and(char::is_alphabetic) {
+                    breaks.push((i + 1, i + 1));
+                }
+            }
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    breaks
+}
+
+/// Set `text` in `rect`, wrapped to its width from the top down, and
+/// return whatever did not fit when the style's overflow is
+/// [`Overflow::Continue`]. At least one line is always drawn, so repeated
+/// calls with the remainder make progress.
+pub fn draw_paragraph(
+    canvas: &mut dyn Canvas,
+    text: &str,
+    rect: &BoundingBox,
+    style: &ParagraphStyle,
+    font: &TextMeasurer,
+) -> Result<Option<String>, Error> {
+    let capacity = |font_size: f32| -> Result<usize, Error> {
+        let metrics = font.measure("", font_size)?;
+        // descenders of a baseline-aligned block hang below the box
+        let spare = match style.v_align {
+            VAlign::Baseline => rect.height() - metrics.ascent,
+            _ => rect.height() - metrics.height(),
+        };
+        if spare < 0.0 {
+            return Ok(0);
+        }
+        let line_height = pt_to_mm(font_size * style.line_spacing);
+        Ok((spare / line_height + 1e-4).floor() as usize + 1)
+    };
+
+    let mut font_size = style.font_size;
+    let mut overflow = style.overflow;
+    let mut lines = wrap(text, rect.width(), font, font_size)?;
+    if let Overflow::Shrink { min_size } = overflow {
+        while lines.len() > capacity(font_size)? && font_size > min_size {
+            font_size = (font_size - SHRINK_STEP).max(min_size);
+            lines = wrap(text, rect.width(), font, font_size)?;
+        }
+        overflow = Overflow::Ellipsis;
+    }
+
+    let shown = lines.len().min(capacity(font_size)?.max(1));
+    let cut = shown < lines.len();
+    let metrics = font.measure("", font_size)?;
+    let line_height = pt_to_mm(font_size * style.line_spacing);
+    let block = (shown - 1) as f32 * line_height;
+    let first_baseline = match style.v_align {
+        VAlign::Top => rect.top - metrics.ascent,
+        VAlign::Middle => rect.center().1 + (block - metrics.ascent + metrics.descent) / 2.0,
+        VAlign::Bottom => rect.bottom + metrics.descent + block,
+        VAlign::Baseline => rect.bottom + block,
+    };
+    for (i, line) in lines[..shown].iter().enumerate() {
+        let last = i + 1 == shown;
+        let mut content = text[line.range.clone()].trim_end().to_string();
+        if last && cut && overflow == Overflow::Ellipsis {
+            content = ellipsize(&content, rect.width(), font, font_size)?;
+        }
+        let align = match style.align {
+            HAlign::Justify if line.ends_paragraph || (last && cut) => HAlign::Left,
+            align => align,
+        };
+        let baseline = first_baseline - i as f32 * line_height;
+        let line_rect = BoundingBox {
+            bottom: baseline,
+            top: baseline,
+            ..*rect
+        };
+        draw_text_in_box(
+            canvas,
+            &content,
+            &line_rect,
+            align,
+            VAlign::Baseline,
+            font,
+            font_size,
+        )?;
+    }
+
+    Ok(match overflow {
+        Overflow::Continue if cut => Some(text[lines[shown].range.start..].to_string()),
+        _ => None,
+    })
+}
+
+/// Largest size from `font_size` down to `min_size` points at which every
+/// line of `text` fits in `max_width` millimetres, or `min_size` if none
+/// does.
+pub fn fit_font_size(
+    text: &str,
+    max_width: f32,
+    font: &TextMeasurer,
+    font_size: f32,
+    min_size: f32,
+) -> Result<f32, Error> {
+    // widths grow linearly with the size, so the widest line decides
+    let mut widest = 0.0f32;
+    for line in text.split('\n') {
+        widest = widest.max(font.width(line.trim_end(), font_size)?);
+    }
+    if widest <= max_width {
+        return Ok(font_size);
+    }
+    // rounded down to a tenth of a point, which also keeps float error from
+    // pushing the text a hair over the width
+    let fitted = (font_size * max_width / widest * 10.0).floor() / 10.0;
+    Ok(fitted.max(min_size))
+}
+
+/// Draw `text` in `rect` on one line, scaled down from the style's font
+/// size as far as `min_size` to fit the width, and wrapped as a paragraph
+/// at `min_size` when even that is too wide.
+pub fn draw_fitted_text(
+    canvas: &mut dyn Canvas,
+    text: &str,
+    rect: &BoundingBox,
+    style: &ParagraphStyle,
+    min_size: f32,
+    font: &TextMeasurer,
+) -> Result<Option<String>, Error> {
+    let font_size = fit_font_size(text, rect.width(), font, style.font_size, min_size)?;
+    let style = ParagraphStyle {
+        font_size,
+        ..*style
+    };
+    draw_paragraph(canvas, text, rect, &style, font)
+}
+
+/// `line` with an ellipsis appended, shortened to fit `max_width`.
+fn ellipsize(
+    line: &str,
+    max_width: f32,
+    font: &TextMeasurer,
+    font_size: f32,
+) -> Result<String, Error> {
+    // "..." for fonts without the ellipsis character
+    let ellipsis = match font.width("\u{2026}", font_size) {
+        Ok(_) => "\u{2026}",
+        Err(Error::Unmappable(_)) => "...",
+        Err(e) => return Err(e),
+    };
+    let mut kept = line;
+    loop {
+        let candidate = format!("{}{}", kept.trim_end(), ellipsis);
+        if kept.is_empty() || font.width(&candidate, font_size)? <= max_width {
+            return Ok(candidate);
+        }
+        let mut chars = kept.chars();
+        chars.next_back();
+        kept = chars.as_str();
+    }
+}
+
+// Helvetica metrics from the Adobe core font AFMs, in 1/1000 em. Both
+// weights share the same ascender and descender.
+const HELVETICA_ASCENDER: i16 = 718;
+const HELVETICA_DESCENDER: i16 = -207;
+
+/// Advance widths of one builtin face over the WinAnsi character set.
+struct AfmWidths {
+    /// U+0020..=U+007E.
+    ascii: [u16; 95],
+    /// U+00A0..=U+00FF, which WinAnsi encodes as Latin-1.
+    latin1: [u16; 96],
+    /// WinAnsi codes 0x80..=0x9F with their characters and widths.
+    win_ansi: [(u8, char, u16); 27],
+}
+
+#[rustfmt::skip]
+const HELVETICA: AfmWidths = AfmWidths {
+    ascii: [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+    ],
+    latin1: [
+        278, 333, 556, 556, 556, 556, 260, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+        400, 584, 333, 333, 333, 556, 537, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+        667, 667, 667, 667, 667, 667, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+        722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+        556, 556, 556, 556, 556, 556, 889, 500, 556, 556, 556, 556, 278, 278, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 584, 611, 556, 556, 556, 556, 500, 556, 500,
+    ],
+    win_ansi: [
+        (0x80, '€', 556), (0x82, '‚', 222), (0x83, 'ƒ', 556), (0x84, '„', 333),
+        (0x85, '…', 1000), (0x86, '†', 556), (0x87, '‡', 556), (0x88, 'ˆ', 333),
+        (0x89, '‰', 1000), (0x8A, 'Š', 667), (0x8B, '‹', 333), (0x8C, 'Œ', 1000),
+        (0x8E, 'Ž', 611), (0x91, '‘', 222), (0x92, '’', 222), (0x93, '“', 333),
+        (0x94, '”', 333), (0x95, '•', 350), (0x96, '–', 556), (0x97, '—', 1000),
+        (0x98, '˜', 333), (0x99, '™', 1000), (0x9A, 'š', 500), (0x9B, '›', 333),
+        (0x9C, 'œ', 944), (0x9E, 'ž', 500), (0x9F, 'Ÿ', 667),
+    ],
+};
+
+#[rustfmt::skip]
+const HELVETICA_BOLD: AfmWidths = AfmWidths {
+    ascii: [
+        278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+        975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+        333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+        611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+    ],
+    latin1: [
+        278, 333, 556, 556, 556, 556, 280, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+        400, 584, 333, 333, 333, 611, 556, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+        722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+        722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+        556, 556, 556, 556, 556, 556, 889, 556, 556, 556, 556, 556, 278, 278, 278, 278,
+        611, 611, 611, 611, 611, 611, 611, 584, 611, 611, 611, 611, 611, 556, 611, 556,
+    ],
+    win_ansi: [
+        (0x80, '€', 556), (0x82, '‚', 278), (0x83, 'ƒ', 556), (0x84, '„', 500),
+        (0x85, '…', 1000), (0x86, '†', 556), (0x87, '‡', 556), (0x88, 'ˆ', 333),
+        (0x89, '‰', 1000), (0x8A, 'Š', 667), (0x8B, '‹', 333), (0x8C, 'Œ', 1000),
+        (0x8E, 'Ž', 611), (0x91, '‘', 278), (0x92, '’', 278), (0x93, '“', 500),
+        (0x94, '”', 500), (0x95, '•', 350), (0x96, '–', 556), (0x97, '—', 1000),
+        (0x98, '˜', 333), (0x99, '™', 1000), (0x9A, 'š', 556), (0x9B, '›', 333),
+        (0x9C, 'œ', 944), (0x9E, 'ž', 500), (0x9F, 'Ÿ', 667),
+    ],
+};
+
+/// WinAnsi code and width of `c` in the builtin Helvetica or Helvetica-Bold.
+fn helvetica_glyph(c: char, bold: bool) -> Option<(u8, u16)> {
+    let widths = if bold { &HELVETICA_BOLD } else { &HELVETICA };
+    match c as u32 {
+        code @ 0x20..=0x7E => Some((code as u8, widths.ascii[code as usize - 0x20])),
+        code @ 0xA0..=0xFF => Some((code as u8, widths.latin1[code as usize - 0xA0])),
+        _ => widths
+            .win_ansi
+            .iter()
+            .find(|(_, ch, _)| *ch == c)
+            .map(|&(code, _, width)| (code, width)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::{Command, RecordingCanvas};
+
+    const ROBOTO: &[u8] = include_bytes!("../vendor/printpdf/assets/fonts/RobotoMedium.ttf");
+
+    fn roboto() -> TextMeasurer {
+        TextMeasurer::new(ROBOTO.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn width_scales_with_font_size() {
+        let m = roboto();
+        let w10 = m.width("Jane Doe", 10.0).unwrap();
+        let w20 = m.width("Jane Doe", 20.0).unwrap();
+        assert!(w10 > 0.0);
+        assert!((w20 - 2.0 * w10).abs() < 1e-4);
+    }
+
+    #[test]
+    fn pairs_are_kerned() {
+        let m = roboto();
+        let glyphs = m.glyphs("AV").unwrap();
+        assert!(glyphs[0].kerning < 0, "{:?}", glyphs);
+        assert_eq!(glyphs[1].kerning, 0);
+
+        let upem = m.units_per_em(0) as f32;
+        let unkerned: u16 = glyphs.iter().map(|g| g.advance).sum();
+        let kerned = m.width("AV", 10.0).unwrap();
+        assert!(kerned < pt_to_mm(unkerned as f32 * 10.0 / upem));
+    }
+
+    #[test]
+    fn unmappable_characters_are_errors() {
+        let m = roboto();
+        assert!(matches!(
+            m.measure("a\u{4e2d}b", 10.0),
+            Err(Error::Unmappable('\u{4e2d}'))
+        ));
+    }
+
+    #[test]
+    fn bbox_covers_ink_only() {
+        let m = roboto();
+        let metrics = m.measure("xo", 10.0).unwrap();
+        // no ascenders or descenders: the ink sits well inside the line box
+        assert!(metrics.bbox.top < metrics.ascent);
+        assert!(metrics.bbox.bottom > -metrics.descent);
+        assert!(metrics.bbox.left >= 0.0 && metrics.bbox.right <= metrics.width);
+
+        let blank = m.measure("  ", 10.0).unwrap();
+        assert!(blank.width > 0.0);
+        assert_eq!(blank.bbox.width(), 0.0);
+    }
+
+    #[test]
+    fn helvetica_uses_afm_widths_and_win_ansi() {
+        let m = TextMeasurer::helvetica();
+        // "Hé" = 722 + 556 thousandths of an em
+        let width = m.width("Hé", 10.0).unwrap();
+        assert!((width - pt_to_mm(12.78)).abs() < 1e-4);
+        assert_eq!(m.glyphs("€").unwrap()[0].id, GlyphId(0x80));
+        assert!(matches!(m.measure("Ł", 10.0), Err(Error::Unmappable('Ł'))));
+    }
+
+    #[test]
+    fn missing_characters_fall_back_glyph_by_glyph() {
+        let m = TextMeasurer::helvetica().with_fallback(roboto());
+        let fonts: Vec<usize> = m.glyphs("ΔdB").unwrap().iter().map(|g| g.font).collect();
+        assert_eq!(fonts, [1, 0, 0]);
+
+        let delta = roboto().width("Δ", 10.0).unwrap();
+        let db = TextMeasurer::helvetica().width("dB", 10.0).unwrap();
+        assert!((m.width("ΔdB", 10.0).unwrap() - (delta + db)).abs() < 1e-4);
+
+        // only when no face has the glyph
+        assert!(matches!(
+            m.measure("Δ\u{25a1}", 10.0),
+            Err(Error::Unmappable('\u{25a1}'))
+        ));
+    }
+
+    #[test]
+    fn fallback_runs_are_drawn_in_their_own_font() {
+        let m = TextMeasurer::helvetica().with_fallback(roboto());
+        let mut canvas = RecordingCanvas::default();
+        draw_text_in_box(
+            &mut canvas,
+            "a Δ b",
+            &CELL,
+            HAlign::Left,
+            VAlign::Baseline,
+            &m,
+            10.0,
+        )
+        .unwrap();
+
+        let runs: Vec<(&str, usize, f32)> = canvas
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::Text { text, font, x, .. } => Some((text.as_str(), *font, *x)),
+                _ => None,
+            })
+            .collect();
+        let texts: Vec<(&str, usize)> = runs.iter().map(|&(t, f, _)| (t, f)).collect();
+        assert_eq!(texts, [("a ", 0), ("Δ", 1), (" b", 0)]);
+        assert_eq!(runs[1].2, CELL.left + m.width("a ", 10.0).unwrap());
+        assert!((runs[2].2 - (CELL.left + m.width("a Δ", 10.0).unwrap())).abs() < 1e-4);
+    }
+
+    fn drawn_text(canvas: &RecordingCanvas) -> Vec<(&str, f32, f32)> {
+        canvas
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::Text { text, x, y, .. } => Some((text.as_str(), *x, *y)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    const CELL: BoundingBox = BoundingBox {
+        left: 10.0,
+        bottom: 20.0,
+        right: 70.0,
+        top: 30.0,
+    };
+
+    #[test]
+    fn text_is_aligned_horizontally_by_its_measured_width() {
+        let m = TextMeasurer::helvetica();
+        let width = m.width("Jane Doe", 12.0).unwrap();
+        for (align, x) in [
+            (HAlign::Left, 10.0),
+            (HAlign::Center, 40.0 - width / 2.0),
+            (HAlign::Right, 70.0 - width),
+        ] {
+            let mut canvas = RecordingCanvas::default();
+            draw_text_in_box(
+                &mut canvas,
+                "Jane Doe",
+                &CELL,
+                align,
+                VAlign::Baseline,
+                &m,
+                12.0,
+            )
+            .unwrap();
+            assert_eq!(drawn_text(&canvas), [("Jane Doe", x, 20.0)]);
+        }
+    }
+
+    #[test]
+    fn text_is_aligned_vertically_by_the_font_metrics() {
+        let m = TextMeasurer::helvetica();
+        let metrics = m.measure("Jane Doe", 12.0).unwrap();
+        for (align, y) in [
+            (VAlign::Top, 30.0 - metrics.ascent),
+            (VAlign::Bottom, 20.0 + metrics.descent),
+            (VAlign::Baseline, 20.0),
+        ] {
+            let mut canvas = RecordingCanvas::default();
+            draw_text_in_box(
+                &mut canvas,
+                "Jane Doe",
+                &CELL,
+                HAlign::Left,
+                align,
+                &m,
+                12.0,
+            )
+            .unwrap();
+            assert_eq!(drawn_text(&canvas)[0].2, y);
+        }
+
+        // the line box is centered, whatever glyphs the text contains
+        let mut canvas = RecordingCanvas::default();
+        draw_text_in_box(
+            &mut canvas,
+            "ace",
+            &CELL,
+            HAlign::Left,
+            VAlign::Middle,
+            &m,
+            12.0,
+        )
+        .unwrap();
+        let baseline = drawn_text(&canvas)[0].2;
+        let (top, bottom) = (baseline + metrics.ascent, baseline - metrics.descent);
+        assert!(((top - 30.0) - (20.0 - bottom)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn justified_words_span_the_box() {
+        let m = TextMeasurer::helvetica();
+        let mut canvas = RecordingCanvas::default();
+        draw_text_in_box(
+            &mut canvas,
+            "one two  three",
+            &CELL,
+            HAlign::Justify,
+            VAlign::Middle,
+            &m,
+            10.0,
+        )
+        .unwrap();
+        let words = drawn_text(&canvas);
+        assert_eq!(words.len(), 3);
+        assert_eq!(words[0].1, 10.0);
+        let (last, x, _) = words[2];
+        assert!((x + m.width(last, 10.0).unwrap() - 70.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn lines_wrap_at_spaces_within_the_width() {
+        let m = TextMeasurer::helvetica();
+        let text = "Hearing within normal limits in both ears. Retest in one year.";
+        let lines = wrap_lines(text, 40.0, &m, 9.0).unwrap();
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|l| m.width(l, 9.0).unwrap() <= 40.0));
+        assert_eq!(lines.join(" "), text);
+    }
+
+    #[test]
+    fn french_punctuation_stays_with_its_word() {
+        let m = TextMeasurer::helvetica();
+        let width = m.width("aaaa bbbb", 9.0).unwrap() + 0.01;
+        assert_eq!(
+            wrap_lines("aaaa bbbb ?", width, &m, 9.0).unwrap(),
+            ["aaaa", "bbbb ?"]
+        );
+
+        let width = m.width("« aaaa »", 9.0).unwrap() + 0.01;
+        assert_eq!(
+            wrap_lines("xx « aaaa »", width, &m, 9.0).unwrap(),
+            ["xx", "« aaaa »"]
+        );
+
+        // no-break spaces never break
+        let width = m.width("50\u{a0}dB", 9.0).unwrap() + 0.01;
+        assert_eq!(
+            wrap_lines("xx 50\u{a0}dB", width, &m, 9.0).unwrap(),
+            ["xx", "50\u{a0}dB"]
+        );
+    }
+
+    #[test]
+    fn compounds_break_after_their_hyphen() {
+        let m = TextMeasurer::helvetica();
+        let width = m.width("peut-", 9.0).unwrap() + 0.01;
+        assert_eq!(
+            wrap_lines("peut-être", width, &m, 9.0).unwrap(),
+            ["peut-", "être"]
+        );
+    }
+
+    #[test]
+    fn line_breaks_are_kept_and_long_words_split() {
+        let m = TextMeasurer::helvetica();
+        assert_eq!(
+            wrap_lines("one\n\ntwo", 50.0, &m, 9.0).unwrap(),
+            ["one", "", "two"]
+        );
+
+        let width = m.width("abc", 9.0).unwrap() + 0.01;
+        assert_eq!(
+            wrap_lines("abcdefgh", width, &m, 9.0).unwrap(),
+            ["abc", "def", "gh"]
+        );
+    }
+
+    const NOTES: &str = "Bilateral mild sensorineural hearing loss. Amplification \
+        discussed with the patient; follow-up in six months to review the \
+        hearing aid trial and repeat the speech audiometry.";
+
+    fn paragraph_rect(lines: f32) -> BoundingBox {
+        // a 9pt Helvetica line box is 3.26 mm; baselines are 3.81 mm apart
+        BoundingBox {
+            left: 0.0,
+            bottom: 0.0,
+            right: 60.0,
+            top: 3.3 + (lines - 1.0) * 3.81,
+        }
+    }
+
+    #[test]
+    fn continued_paragraphs_hand_back_the_rest() {
+        let m = TextMeasurer::helvetica();
+        let style = ParagraphStyle {
+            overflow: Overflow::Continue,
+            ..ParagraphStyle::new(9.0)
+        };
+        let mut canvas = RecordingCanvas::default();
+        let rest = draw_paragraph(&mut canvas, NOTES, &paragraph_rect(2.0), &style, &m)
+            .unwrap()
+            .unwrap();
+
+        let drawn: Vec<&str> = drawn_text(&canvas).iter().map(|t| t.0).collect();
+        assert_eq!(drawn.len(), 2);
+        assert_eq!(format!("{} {} {}", drawn[0], drawn[1], rest), NOTES);
+
+        let rect = paragraph_rect(10.0);
+        let mut canvas = RecordingCanvas::default();
+        assert_eq!(
+            draw_paragraph(&mut canvas, NOTES, &rect, &style, &m).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn cut_paragraphs_end_with_an_ellipsis() {
+        let m = TextMeasurer::helvetica();
+        let mut canvas = RecordingCanvas::default();
+        let rest = draw_paragraph(
+            &mut canvas,
+            NOTES,
+            &paragraph_rect(2.0),
+            &ParagraphStyle::new(9.0),
+            &m,
+        )
+        .unwrap();
+        assert_eq!(rest, None);
+
+        let drawn = drawn_text(&canvas);
+        assert_eq!(drawn.len(), 2);
+        assert!(drawn[1].0.ends_with('\u{2026}'));
+        assert!(m.width(drawn[1].0, 9.0).unwrap() <= 60.0);
+    }
+
+    #[test]
+    fn shrinking_paragraphs_get_smaller_until_they_fit() {
+        let m = TextMeasurer::helvetica();
+        let style = ParagraphStyle {
+            overflow: Overflow::Shrink { min_size: 5.0 },
+            ..ParagraphStyle::new(9.0)
+        };
+        let mut canvas = RecordingCanvas::default();
+        draw_paragraph(&mut canvas, NOTES, &paragraph_rect(3.0), &style, &m).unwrap();
+
+        let sizes: Vec<f32> = canvas
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::Text { font_size, .. } => Some(*font_size),
+                _ => None,
+            })
+            .collect();
+        assert!(sizes.iter().all(|s| *s < 9.0 && *s >= 5.0));
+        let drawn: Vec<&str> = drawn_text(&canvas).iter().map(|t| t.0).collect();
+        assert_eq!(drawn.join(" "), NOTES);
+    }
+
+    const NAME: &str = "MARIE-ÈVE DESROCHERS-LAFLAMME";
+
+    #[test]
+    fn names_shrink_to_fit_the_width() {
+        let m = TextMeasurer::helvetica();
+        assert_eq!(fit_font_size("ROY", 90.0, &m, 18.0, 11.0).unwrap(), 18.0);
+
+        let size = fit_font_size(NAME, 90.0, &m, 18.0, 11.0).unwrap();
+        assert!((11.0..18.0).contains(&size));
+        assert!(m.width(NAME, size).unwrap() <= 90.0);
+        assert!(m.width(NAME, size + 0.1).unwrap() > 90.0);
+
+        let mut canvas = RecordingCanvas::default();
+        let rect = BoundingBox {
+            left: 0.0,
+            bottom: 0.0,
+            right: 90.0,
+            top: 12.0,
+        };
+        let style = ParagraphStyle {
+            v_align: VAlign::Baseline,
+            ..ParagraphStyle::new(18.0)
+        };
+        draw_fitted_text(&mut canvas, NAME, &rect, &style, 11.0, &m).unwrap();
+        assert_eq!(drawn_text(&canvas), [(NAME, 0.0, 0.0)]);
+    }
+
+    #[test]
+    fn names_too_long_at_the_minimum_size_wrap() {
+        let m = TextMeasurer::helvetica();
+        let rect = BoundingBox {
+            left: 0.0,
+            bottom: 0.0,
+            right: 40.0,
+            top: 12.0,
+        };
+        let style = ParagraphStyle {
+            v_align: VAlign::Baseline,
+            ..ParagraphStyle::new(18.0)
+        };
+        let mut canvas = RecordingCanvas::default();
+        draw_fitted_text(&mut canvas, NAME, &rect, &style, 11.0, &m).unwrap();
+
+        let drawn = drawn_text(&canvas);
+        assert_eq!(drawn.len(), 2);
+        assert_eq!(drawn[0].0, "MARIE-ÈVE");
+        assert!(canvas
+            .commands
+            .iter()
+            .all(|c| !matches!(c, Command::Text { font_size, .. } if *font_size != 11.0)));
+        // the last line keeps the baseline, the first one goes above it
+        assert_eq!(drawn[1].2, 0.0);
+        assert!(drawn[0].2 > 0.0);
+    }
+}
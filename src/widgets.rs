@@ -0,0 +1,95 @@
+use printpdf::Mm;
+
+use crate::canvas::{Canvas, Paint, Path};
+
+const C: f32 = 0.55191505; // bezier approximation constant
+
+/* helper: draw a capsule-like rectangle with curved left and right sides */
+pub fn draw_curved_sides_rect(canvas: &mut dyn Canvas, origin: (Mm, Mm), w: Mm, h: Mm) {
+    let r = h.0 / 2.0; // radius for the side curves
+    draw_rounded_rect(canvas, origin, w, h, Mm(r));
+}
+
+/* helper: draw a rectangle with rounded corners */
+pub fn draw_rounded_rect(canvas: &mut dyn Canvas, origin: (Mm, Mm), w: Mm, h: Mm, r: Mm) {
+    let (x0, y0) = (origin.0 .0, origin.1 .0);
+    let (x1, y1) = (x0 + w.0, y0 + h.0);
+    let r = r.0;
+    let c = r * C;
+
+    let path = Path::new()
+        // start at bottom left after corner, along the bottom edge
+        .move_to(x0 + r, y0)
+        .line_to(x1 - r, y0)
+        // bottom-right corner, right edge
+        .curve_to((x1 - r + c, y0), (x1, y0 + r - c), (x1, y0 + r))
+        .line_to(x1, y1 - r)
+        // top-right corner, top edge
+        .curve_to((x1, y1 - r + c), (x1 - r + c, y1), (x1 - r, y1))
+        .line_to(x0 + r, y1)
+        // top-left corner, left edge
+        .curve_to((x0 + r - c, y1), (x0, y1 - r + c), (x0, y1 - r))
+        .line_to(x0, y0 + r)
+        // bottom-left corner
+        .curve_to((x0, y0 + r - c), (x0 + r - c, y0), (x0 + r, y0))
+        .close();
+
+    canvas.draw_path(&path, Paint::Stroke);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::{Command, PathOp, RecordingCanvas};
+
+    fn only_path(canvas: &RecordingCanvas) -> &Path {
+        match canvas.commands.as_slice() {
+            [Command::DrawPath(path, Paint::Stroke)] => path,
+            other => panic!("expected one stroked path, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rounded_rect_has_four_corner_curves() {
+        let mut canvas = RecordingCanvas::default();
+        draw_rounded_rect(
+            &mut canvas,
+            (Mm(10.0), Mm(20.0)),
+            Mm(40.0),
+            Mm(10.0),
+            Mm(2.0),
+        );
+
+        let path = only_path(&canvas);
+        let curves = path
+            .ops
+            .iter()
+            .filter(|op| matches!(op, PathOp::CurveTo(..)))
+            .count();
+        assert_eq!(curves, 4);
+        assert_eq!(path.ops[0], PathOp::MoveTo(12.0, 20.0));
+        assert_eq!(path.ops.last(), Some(&PathOp::Close));
+    }
+
+    #[test]
+    fn curved_sides_rect_uses_half_height_radius() {
+        let mut canvas = RecordingCanvas::default();
+        draw_curved_sides_rect(&mut canvas, (Mm(0.0), Mm(0.0)), Mm(30.0), Mm(10.0));
+
+        let path = only_path(&canvas);
+        assert_eq!(path.ops[0], PathOp::MoveTo(5.0, 0.0));
+        assert_eq!(path.ops[1], PathOp::LineTo(25.0, 0.0));
+        // the right side is a half circle through (30, 5)
+        assert!(matches!(path.ops[2], PathOp::CurveTo(_, _, (x, y)) if x == 30.0 && y == 5.0));
+    }
+
+    #[test]
+    fn widgets_leave_graphics_state_alone() {
+        let mut canvas = RecordingCanvas::default();
+        draw_rounded_rect(&mut canvas, (Mm(0.0), Mm(0.0)), Mm(10.0), Mm(10.0), Mm(1.0));
+        assert!(canvas
+            .commands
+            .iter()
+            .all(|c| matches!(c, Command::DrawPath(..))));
+    }
+}